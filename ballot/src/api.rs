@@ -1,17 +1,38 @@
 use exonum::api::{Api as ExonumApi, ApiError};
-use exonum::blockchain::{ApiContext, Blockchain};
-use exonum::crypto::{CryptoHash, Hash, PublicKey, SecretKey};
+use exonum::blockchain::{ApiContext, BlockProof, Blockchain, Schema as CoreSchema, Transaction,
+                         TransactionErrorType, TransactionSet};
+use exonum::crypto::{self, CryptoHash, Hash, PublicKey, SecretKey, Signature, SIGNATURE_LENGTH};
+use exonum::encoding::serialize::FromHex;
+use exonum::messages::{Message, RawMessage, HEADER_LENGTH};
 use exonum::node::{ApiSender, TransactionSend};
-use exonum::storage::StorageValue;
+use exonum::storage::{MapProof, Snapshot, StorageValue};
 
+use iron::headers::ContentType;
 use iron::prelude::*;
+use iron::status;
 
 use router::Router;
 use serde_json;
 use bodyparser;
 
-use schema::{BallotData, ProposalList, Schema};
-use transactions::{Ballot, Vote};
+use std::io::Read;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::u64;
+
+use SERVICE_ID;
+use blobs::BlobStore;
+use error::{Error as ServiceError, ErrorCode};
+use events::{EventHub, MAX_EVENT_STREAMS};
+use schema::{answer_sheet_key, question_tally_key, Amendment, Answer, AnswerSheet, Attachment,
+             BallotConfig, BallotData, BallotStatus, ConfigProposal, Finalization, Outcome,
+             ProposalList, RevisionHistory, Schema, Sponsorship, Tally, VoteRecord, VotingMode,
+             TALLIES_TABLE_INDEX};
+use transactions::{AmendBallot, Ballot, FinalizeBallot, Nominate, ProposeConfig, SponsorBallot,
+                   Transactions as BallotTransactions, Vote, VoteConfig, VoteQuestions};
 
 pub type VotesInfo = Option<Vec<Option<Vote>>>;
 
@@ -24,6 +45,230 @@ pub struct BallotHashInfo {
     pub votes: VotesInfo,
 }
 
+const DEFAULT_PAGE_LIMIT: usize = 100;
+const MAX_PAGE_LIMIT: usize = 1000;
+/// Most ballots a `/v2/ballots` page reads, see `BallotQuery`.
+const MAX_PAGE_SCAN: usize = 5000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BallotOrder {
+    Oldest,
+    Newest,
+}
+
+impl FromStr for BallotOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<BallotOrder, String> {
+        match s {
+            "oldest" => Ok(BallotOrder::Oldest),
+            "newest" => Ok(BallotOrder::Newest),
+            _ => Err(format!("unknown order: {}", s)),
+        }
+    }
+}
+
+/// Query parameters of `/v2/ballots`, every field is optional.
+///
+/// `cursor` is the `next_cursor` of a previous page, `offset` is the ordinal to start from
+/// and is ignored if `cursor` is present.
+///
+/// `author`, `category`, `tag` and the deadline range, which `status` narrows to ballots
+/// before or after their deadline, select ballots from an index. `not_voted_by` and the
+/// exact status are checked on each selected ballot, so a page reads at most
+/// `MAX_PAGE_SCAN` ballots and may come back short, or empty, with a `next_cursor` to go
+/// on from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BallotQuery {
+    pub offset: Option<u64>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    pub order: Option<BallotOrder>,
+    pub status: Option<BallotStatus>,
+    pub author: Option<PublicKey>,
+    pub deadline_from: Option<u64>,
+    pub deadline_to: Option<u64>,
+    pub not_voted_by: Option<PublicKey>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl BallotQuery {
+    pub fn to_query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(offset) = self.offset {
+            params.push(format!("offset={}", offset));
+        }
+        if let Some(ref cursor) = self.cursor {
+            params.push(format!("cursor={}", cursor));
+        }
+        if let Some(limit) = self.limit {
+            params.push(format!("limit={}", limit));
+        }
+        if let Some(order) = self.order {
+            let order = match order {
+                BallotOrder::Oldest => "oldest",
+                BallotOrder::Newest => "newest",
+            };
+            params.push(format!("order={}", order));
+        }
+        if let Some(status) = self.status {
+            let status = match status {
+                BallotStatus::Open => "open",
+                BallotStatus::Closed => "closed",
+                BallotStatus::SeekingSponsors => "seeking_sponsors",
+                BallotStatus::InDiscussion => "in_discussion",
+                BallotStatus::Nominating => "nominating",
+            };
+            params.push(format!("status={}", status));
+        }
+        if let Some(ref author) = self.author {
+            params.push(format!("author={}", author));
+        }
+        if let Some(deadline_from) = self.deadline_from {
+            params.push(format!("deadline_from={}", deadline_from));
+        }
+        if let Some(deadline_to) = self.deadline_to {
+            params.push(format!("deadline_to={}", deadline_to));
+        }
+        if let Some(ref not_voted_by) = self.not_voted_by {
+            params.push(format!("not_voted_by={}", not_voted_by));
+        }
+        if let Some(ref category) = self.category {
+            params.push(format!("category={}", category));
+        }
+        if let Some(ref tag) = self.tag {
+            params.push(format!("tag={}", tag));
+        }
+
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+
+    /// Inclusive deadline range selected by the deadline and status filters.
+    fn deadline_range(&self, latest_height: u64) -> Option<(u64, u64)> {
+        if self.status.is_none() && self.deadline_from.is_none() && self.deadline_to.is_none() {
+            return None;
+        }
+
+        let mut from = self.deadline_from.unwrap_or(0);
+        let mut to = self.deadline_to.unwrap_or(u64::MAX);
+        match self.status {
+            Some(BallotStatus::Open)
+            | Some(BallotStatus::SeekingSponsors)
+            | Some(BallotStatus::InDiscussion)
+            | Some(BallotStatus::Nominating) => from = from.max(latest_height),
+            Some(BallotStatus::Closed) => match latest_height.checked_sub(1) {
+                Some(height) => to = to.min(height),
+                None => return Some((1, 0)),
+            },
+            None => (),
+        }
+        Some((from, to))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BallotPage {
+    pub ballots: Vec<BallotHashInfo>,
+    pub next_cursor: Option<String>,
+}
+
+fn encode_cursor(ordinal: u64) -> String {
+    format!("{:016x}", ordinal)
+}
+
+fn decode_cursor(cursor: &str) -> Result<u64, ApiError> {
+    u64::from_str_radix(cursor, 16)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid cursor: {}", cursor)))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProposalResult {
+    pub id: u64,
+    pub subject: String,
+    pub votes: u64,
+}
+
+/// Proof of a stored tally: `block_info` commits to the service tables through its
+/// `state_hash`, `to_table` leads from it to the `ballot.tallies` root and `to_tally`
+/// from that root to the tally.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TallyProof {
+    pub block_info: BlockProof,
+    pub to_table: MapProof<Hash, Hash>,
+    pub to_tally: MapProof<Hash, Tally>,
+}
+
+/// Results of one question of a ballot. `proof` leads from the `ballot.tallies` root
+/// in `BallotResults::proof` to the question's tally and is present along with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionResult {
+    pub id: u64,
+    pub subject: String,
+    pub mode: VotingMode,
+    pub proposals: Vec<ProposalResult>,
+    pub outcome: Outcome,
+    pub proof: Option<MapProof<Hash, Tally>>,
+}
+
+/// Results of a ballot. `electorate` is the number of vote slots reserved when the
+/// ballot was posted, `proof` is only present once the ballot is closed,
+/// `sponsorship` for ballots posted with `min_sponsors` and `finalization` once it
+/// was finalized. Ballots with questions have their results in `questions` and
+/// `votes_cast` counts answer sheets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BallotResults {
+    pub proposals_hash: Hash,
+    pub status: BallotStatus,
+    pub deadline: u64,
+    pub proposals: Vec<ProposalResult>,
+    pub questions: Vec<QuestionResult>,
+    pub electorate: u64,
+    pub votes_cast: u64,
+    pub participation_rate: f64,
+    pub non_voters: Vec<PublicKey>,
+    pub quorum: u64,
+    pub quorum_reached: bool,
+    pub outcome: Outcome,
+    pub proof: Option<TallyProof>,
+    pub sponsorship: Option<Sponsorship>,
+    pub finalization: Option<Finalization>,
+}
+
+/// Participation of a voter in ballots, only closed ballots count toward
+/// `participation_rate` since open ones can still be voted on. `ballots_closed` counts
+/// the closed ballots the voter was in the electorate of.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParticipationStats {
+    pub votes_cast: u64,
+    pub ballots_total: u64,
+    pub ballots_closed: u64,
+    pub closed_voted: u64,
+    pub closed_missed: u64,
+    /// `closed_voted / ballots_closed`, `None` until some ballot is closed.
+    pub participation_rate: Option<f64>,
+}
+
+/// Answer sheet a voter sent to a ballot with questions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnswerRecord {
+    pub proposals_hash: Hash,
+    pub answer_sheet: AnswerSheet,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoterHistory {
+    pub voter: PublicKey,
+    pub votes: Vec<VoteRecord>,
+    pub answers: Vec<AnswerRecord>,
+    pub stats: ParticipationStats,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BallotResponse {
     pub tx_hash: Hash,
@@ -36,11 +281,130 @@ pub struct VoteRequest {
     pub proposal_subject: String,
 }
 
+/// Write-in proposal, the service assigns its id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NominationRequest {
+    pub subject: String,
+    pub description: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct VoteResponse {
     pub tx_hash: Hash,
 }
 
+/// Transaction signed by the client, either as json or as hex encoded raw message.
+///
+/// json example:
+/// {"tx_hex": "0000010000000000..."}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SignedTransaction {
+    Hex { tx_hex: String },
+    Json(BallotTransactions),
+}
+
+impl SignedTransaction {
+    fn into_transaction(self) -> Result<Box<Transaction>, ApiError> {
+        match self {
+            SignedTransaction::Json(tx) => Ok(tx.into()),
+            SignedTransaction::Hex { tx_hex } => {
+                let bytes = Vec::<u8>::from_hex(&tx_hex)
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid tx_hex: {}", e)))?;
+                if bytes.len() < HEADER_LENGTH + SIGNATURE_LENGTH {
+                    Err(ApiError::BadRequest("Transaction is too short".into()))?
+                }
+
+                let raw = RawMessage::from_vec(bytes);
+                if raw.service_id() != SERVICE_ID {
+                    Err(ApiError::BadRequest(format!(
+                        "Unexpected service id: {}",
+                        raw.service_id()
+                    )))?
+                }
+
+                let tx = BallotTransactions::tx_from_raw(raw)
+                    .map_err(|e| ApiError::BadRequest(format!("Invalid transaction: {}", e)))?;
+                Ok(tx.into())
+            }
+        }
+    }
+}
+
+/// Content of an attachment, hex encoded like `SignedTransaction::Hex`, with the
+/// signature of an eligible voter over the content hash.
+///
+/// json example:
+/// {"from": "d7c8...", "content_hex": "255044462d312e34...", "signature": "9f1e..."}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentUpload {
+    pub from: PublicKey,
+    pub content_hex: String,
+    pub signature: Signature,
+}
+
+/// Longest upload body besides the hex content, which takes two characters per byte.
+const MAX_UPLOAD_OVERHEAD: u64 = 1024;
+
+/// Attachment anchored with `hash` and whether the node serves its content.
+/// `attachment` is `None` if no proposal attaches the hash, `available` is true once
+/// the node stores content matching both the hash and the anchored size.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentStatus {
+    pub hash: Hash,
+    pub attachment: Option<Attachment>,
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionResponse {
+    pub tx_hash: Hash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigResponse {
+    pub tx_hash: Hash,
+    pub config_hash: Hash,
+}
+
+/// Longest wait allowed for `/v1/transactions/:hash/status?wait_ms=`. Waiting
+/// requests hold an iron worker thread, clients poll again for longer waits.
+const MAX_STATUS_WAIT_MS: u64 = 2_000;
+const STATUS_POLL_INTERVAL_MS: u64 = 50;
+/// Most status requests waiting at once, others get the current status right away.
+const MAX_STATUS_WAITERS: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// Transaction isn't known to the node yet.
+    Unknown,
+    /// Transaction is in the pool, waiting for a block.
+    Pending,
+    /// Transaction is committed and executed successfully.
+    Committed { height: u64 },
+    /// Transaction is committed but its execution failed, `error_code` is
+    /// `None` if execution panicked or the code isn't a service `ErrorCode`.
+    /// `details` are the values of the failed check decoded from the stored description
+    /// and `description` their display text, or the stored description of other errors.
+    Failed {
+        height: u64,
+        code: Option<u8>,
+        error_code: Option<ErrorCode>,
+        description: Option<String>,
+        details: Option<ServiceError>,
+    },
+}
+
+impl TransactionStatus {
+    pub fn is_committed(&self) -> bool {
+        match *self {
+            TransactionStatus::Committed { .. } | TransactionStatus::Failed { .. } => true,
+            TransactionStatus::Unknown | TransactionStatus::Pending => false,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PrivateApi {
     channel: ApiSender,
@@ -50,33 +414,191 @@ pub struct PrivateApi {
 #[derive(Clone)]
 pub struct PublicApi {
     blockchain: Blockchain,
+    channel: ApiSender,
+    events: EventHub,
+    blobs: BlobStore,
+    status_waiters: Arc<AtomicUsize>,
 }
 
 impl PublicApi {
-    pub fn new(context: &ApiContext) -> Self {
+    pub fn new(context: &ApiContext, events: EventHub, blobs: BlobStore) -> Self {
         PublicApi {
             blockchain: context.blockchain().clone(),
+            channel: context.node_channel().clone(),
+            events,
+            blobs,
+            status_waiters: Arc::default(),
         }
     }
 
     fn ballots(&self, offset: u64, limit: usize) -> Vec<BallotHashInfo> {
         let schema = Schema::new(self.blockchain.snapshot());
-        let proposals_hashs = schema.proposals_hash_by_ordinal();
-        let ballots = proposals_hashs
+        let proposals_hashes = schema.proposals_hash_by_ordinal();
+        proposals_hashes
             .iter_from(offset)
             .take(limit)
             .map(|hash| self.ballot_with_proofs(&hash))
-            .collect();
-        ballots
+            .collect()
+    }
+
+    fn ballot_page(&self, query: &BallotQuery) -> Result<BallotPage, ApiError> {
+        let snapshot = self.blockchain.snapshot();
+        let schema = Schema::new(&snapshot);
+        let proposals_hashes = schema.proposals_hash_by_ordinal();
+
+        let newest_first = query.order == Some(BallotOrder::Newest);
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+        let start = match query.cursor {
+            Some(ref cursor) => Some(decode_cursor(cursor)?),
+            None => query.offset,
+        };
+
+        let latest_height = CoreSchema::new(&snapshot).height().0;
+        let ordinals: Box<Iterator<Item = u64>> = match query.deadline_range(latest_height) {
+            Some((from, to)) => {
+                let mut ordinals = Vec::new();
+                if from <= to {
+                    let deadlines = schema.deadlines();
+                    for deadline in deadlines.iter_from(&from).take_while(|d| *d <= to) {
+                        ordinals.extend(schema.ordinals_by_deadline(deadline).iter());
+                    }
+                }
+                ordinals.sort();
+                if newest_first {
+                    ordinals.reverse();
+                }
+                Box::new(ordinals.into_iter())
+            }
+            None => match Self::indexed_ordinals(&schema, query) {
+                Some(mut ordinals) => {
+                    if newest_first {
+                        ordinals.reverse();
+                    }
+                    Box::new(ordinals.into_iter())
+                }
+                None if newest_first => Box::new((0..proposals_hashes.len()).rev()),
+                None => Box::new(0..proposals_hashes.len()),
+            },
+        };
+        let ordinals = ordinals.filter(|ordinal| match start {
+            Some(start) if newest_first => *ordinal <= start,
+            Some(start) => *ordinal >= start,
+            None => true,
+        });
+
+        let mut ballots = Vec::new();
+        let mut next_cursor = None;
+        for (scanned, ordinal) in ordinals.enumerate() {
+            if scanned == MAX_PAGE_SCAN {
+                next_cursor = Some(encode_cursor(ordinal));
+                break;
+            }
+            let proposals_hash = match proposals_hashes.get(ordinal) {
+                Some(proposals_hash) => proposals_hash,
+                None => continue,
+            };
+            if !Self::matches_filters(&schema, &proposals_hash, query) {
+                continue;
+            }
+            if ballots.len() == limit {
+                next_cursor = Some(encode_cursor(ordinal));
+                break;
+            }
+            ballots.push(self.ballot_with_proofs(&proposals_hash));
+        }
+
+        Ok(BallotPage {
+            ballots,
+            next_cursor,
+        })
+    }
+
+    /// Ordinals from the first secondary index a filter of `query` selects, oldest first.
+    /// The other filters are checked by `matches_filters`.
+    fn indexed_ordinals<T: AsRef<Snapshot>>(
+        schema: &Schema<T>,
+        query: &BallotQuery,
+    ) -> Option<Vec<u64>> {
+        let ordinals = if let Some(ref author) = query.author {
+            schema.ordinals_by_author(author).iter().collect()
+        } else if let Some(ref category) = query.category {
+            schema.ordinals_by_category(category).iter().collect()
+        } else if let Some(ref tag) = query.tag {
+            schema.ordinals_by_tag(tag).iter().collect()
+        } else {
+            return None;
+        };
+        Some(ordinals)
+    }
+
+    fn matches_filters<T: AsRef<Snapshot>>(
+        schema: &Schema<T>,
+        proposals_hash: &Hash,
+        query: &BallotQuery,
+    ) -> bool {
+        if let Some(ref author) = query.author {
+            match schema.ballot(proposals_hash) {
+                Some(ref ballot) if ballot.from() == author => (),
+                _ => return false,
+            }
+        }
+
+        if query.category.is_some() || query.tag.is_some() {
+            let proposals = match schema.proposals(proposals_hash) {
+                Some(proposals) => proposals,
+                None => return false,
+            };
+            if let Some(ref category) = query.category {
+                if proposals.category() != Some(category.as_str()) {
+                    return false;
+                }
+            }
+            if let Some(ref tag) = query.tag {
+                if !proposals.tags().contains(tag) {
+                    return false;
+                }
+            }
+        }
+
+        // The deadline range can't tell open ballots from those that don't accept votes
+        // yet.
+        if let Some(status) = query.status {
+            if schema.ballot_status(proposals_hash) != Some(status) {
+                return false;
+            }
+        }
+
+        if let Some(ref voter) = query.not_voted_by {
+            if schema.has_voted(proposals_hash, voter) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn ballot_query(&self, req: &mut Request) -> Result<BallotQuery, ApiError> {
+        Ok(BallotQuery {
+            offset: self.optional_param(req, "offset")?,
+            cursor: self.optional_param(req, "cursor")?,
+            limit: self.optional_param(req, "limit")?,
+            order: self.optional_param(req, "order")?,
+            status: self.optional_param(req, "status")?,
+            author: self.optional_param(req, "author")?,
+            deadline_from: self.optional_param(req, "deadline_from")?,
+            deadline_to: self.optional_param(req, "deadline_to")?,
+            not_voted_by: self.optional_param(req, "not_voted_by")?,
+            category: self.optional_param(req, "category")?,
+            tag: self.optional_param(req, "tag")?,
+        })
     }
 
     fn ballot_with_proofs(&self, proposals_hash: &Hash) -> BallotHashInfo {
         let schema = Schema::new(self.blockchain.snapshot());
         if let Some(ballot_data) = schema.ballot_data_by_proposals_hash().get(proposals_hash) {
             let hash = Some(ballot_data.hash());
-            let tx_ballot = ballot_data.tx_ballot();
-            let proposals_str = tx_ballot.proposals();
-            let proposals = Some(ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap());
+            // The latest revision for amended ballots, the posted list is in `ballot`.
+            let proposals = schema.proposals(proposals_hash);
             let votes = Some(schema.votes(proposals_hash));
             BallotHashInfo {
                 ballot: Some(ballot_data),
@@ -108,6 +630,265 @@ impl PublicApi {
         }
     }
 
+    fn ballot_results(&self, proposals_hash: &Hash) -> Option<BallotResults> {
+        let snapshot = self.blockchain.snapshot();
+        let schema = Schema::new(&snapshot);
+        let core_schema = CoreSchema::new(&snapshot);
+
+        let proposals = schema.proposals(proposals_hash)?;
+        let status = schema.ballot_status(proposals_hash)?;
+        let votes = schema.votes(proposals_hash);
+        let tally = schema
+            .tallies()
+            .get(proposals_hash)
+            .unwrap_or_else(|| Tally::from_votes(&proposals, &votes));
+
+        let electorate_keys = schema.electorate(proposals_hash).unwrap_or_default();
+        let answers = schema.answers();
+        let non_voters = votes
+            .iter()
+            .zip(electorate_keys.voters().iter())
+            .filter(|&(vote, key)| {
+                vote.is_none() && !answers.contains(&answer_sheet_key(proposals_hash, key))
+            })
+            .map(|(_, key)| *key)
+            .collect();
+
+        let electorate = votes.len() as u64;
+        let votes_cast = if proposals.has_questions() {
+            schema
+                .ballot_data_by_proposals_hash()
+                .get(proposals_hash)
+                .map_or(0, |ballot_data| u64::from(ballot_data.votes_cast()))
+        } else {
+            tally.votes_cast()
+        };
+        let participation_rate = if electorate == 0 {
+            0.0
+        } else {
+            votes_cast as f64 / electorate as f64
+        };
+        // Finalized ballots keep the quorum they were decided with.
+        let finalization = schema.finalizations().get(proposals_hash);
+        let quorum = finalization
+            .as_ref()
+            .and_then(|finalization| finalization.quorum)
+            .unwrap_or_else(|| schema.config().quorum.required(electorate));
+
+        let proof = match status {
+            BallotStatus::Closed => core_schema
+                .block_and_precommits(core_schema.height())
+                .map(|block_info| TallyProof {
+                    block_info,
+                    to_table: core_schema
+                        .get_proof_to_service_table(SERVICE_ID, TALLIES_TABLE_INDEX),
+                    to_tally: schema.tallies().get_proof(*proposals_hash),
+                }),
+            // The tally of a ballot that isn't closed can still change.
+            _ => None,
+        };
+        let questions = proposals
+            .questions()
+            .iter()
+            .map(|question| {
+                let key = question_tally_key(proposals_hash, question.id());
+                let tally = schema
+                    .tallies()
+                    .get(&key)
+                    .unwrap_or_else(|| Tally::for_question(question));
+                QuestionResult {
+                    id: question.id(),
+                    subject: question.subject().to_string(),
+                    mode: question.mode(),
+                    proposals: question
+                        .proposals()
+                        .iter()
+                        .map(|proposal| ProposalResult {
+                            id: proposal.id(),
+                            subject: proposal.subject().to_string(),
+                            votes: tally.votes(proposal.id()),
+                        })
+                        .collect(),
+                    outcome: tally.outcome(),
+                    proof: proof.as_ref().map(|_| schema.tallies().get_proof(key)),
+                }
+            })
+            .collect();
+
+        Some(BallotResults {
+            proposals_hash: *proposals_hash,
+            status,
+            deadline: proposals.deadline(),
+            proposals: proposals
+                .proposals()
+                .iter()
+                .map(|proposal| ProposalResult {
+                    id: proposal.id(),
+                    subject: proposal.subject().to_string(),
+                    votes: tally.votes(proposal.id()),
+                })
+                .collect(),
+            questions,
+            electorate,
+            votes_cast,
+            participation_rate,
+            non_voters,
+            quorum,
+            quorum_reached: votes_cast >= quorum,
+            outcome: tally.outcome(),
+            proof,
+            sponsorship: schema.sponsorships().get(proposals_hash),
+            finalization,
+        })
+    }
+
+    fn voter_history(&self, voter: &PublicKey) -> VoterHistory {
+        let snapshot = self.blockchain.snapshot();
+        let schema = Schema::new(&snapshot);
+        let latest_height = CoreSchema::new(&snapshot).height().0;
+
+        let votes = schema.votes_by_voter(voter).iter().collect::<Vec<_>>();
+        let answers = schema
+            .answers_by_voter(voter)
+            .iter()
+            .filter_map(|proposals_hash| {
+                let key = answer_sheet_key(&proposals_hash, voter);
+                schema.answers().get(&key).map(|answer_sheet| AnswerRecord {
+                    proposals_hash,
+                    answer_sheet,
+                })
+            })
+            .collect::<Vec<_>>();
+        // Only ballots `voter` was in the electorate of count as missed.
+        let closed = schema
+            .deadlines()
+            .iter()
+            .take_while(|deadline| *deadline < latest_height)
+            .flat_map(|deadline| schema.ordinals_by_deadline(deadline).iter().collect::<Vec<_>>())
+            .filter_map(|ordinal| schema.proposals_hash_by_ordinal().get(ordinal))
+            .filter(|proposals_hash| {
+                schema
+                    .electorate(proposals_hash)
+                    .map_or(false, |electorate| electorate.position(voter).is_some())
+            })
+            .collect::<Vec<_>>();
+        let ballots_closed = closed.len() as u64;
+        let closed_voted = closed
+            .iter()
+            .filter(|proposals_hash| schema.has_voted(proposals_hash, voter))
+            .count() as u64;
+
+        let participation_rate = if ballots_closed == 0 {
+            None
+        } else {
+            Some(closed_voted as f64 / ballots_closed as f64)
+        };
+        let stats = ParticipationStats {
+            votes_cast: (votes.len() + answers.len()) as u64,
+            ballots_total: schema.proposals_hash_by_ordinal().len(),
+            ballots_closed,
+            closed_voted,
+            closed_missed: ballots_closed - closed_voted,
+            participation_rate,
+        };
+
+        VoterHistory {
+            voter: *voter,
+            votes,
+            answers,
+            stats,
+        }
+    }
+
+    fn transaction_status(&self, tx_hash: &Hash) -> TransactionStatus {
+        let snapshot = self.blockchain.snapshot();
+        let core_schema = CoreSchema::new(&snapshot);
+
+        let result = match core_schema.transaction_results().get(tx_hash) {
+            Some(result) => result,
+            None if core_schema.transactions_pool().contains(tx_hash) => {
+                return TransactionStatus::Pending
+            }
+            None => return TransactionStatus::Unknown,
+        };
+
+        let height = core_schema
+            .transactions_locations()
+            .get(tx_hash)
+            .map_or(0, |location| location.block_height().0);
+        match result {
+            Ok(()) => TransactionStatus::Committed { height },
+            Err(e) => {
+                let code = match e.error_type() {
+                    TransactionErrorType::Code(code) => Some(code),
+                    TransactionErrorType::Panic => None,
+                };
+                let error_code = code.and_then(ErrorCode::from_u8);
+                let details = e.description().and_then(ServiceError::from_description);
+                let description = match details {
+                    Some(ref details) => Some(details.to_string()),
+                    None => e.description().map(String::from),
+                };
+                TransactionStatus::Failed {
+                    height,
+                    code,
+                    error_code,
+                    description,
+                    details,
+                }
+            }
+        }
+    }
+
+    fn wait_transaction_status(&self, tx_hash: &Hash, wait_ms: u64) -> TransactionStatus {
+        let status = self.transaction_status(tx_hash);
+        if status.is_committed() || wait_ms == 0 {
+            return status;
+        }
+        if self.status_waiters.fetch_add(1, Ordering::SeqCst) >= MAX_STATUS_WAITERS {
+            self.status_waiters.fetch_sub(1, Ordering::SeqCst);
+            return status;
+        }
+
+        let timeout = Duration::from_millis(wait_ms.min(MAX_STATUS_WAIT_MS));
+        let started = Instant::now();
+        let status = loop {
+            thread::sleep(Duration::from_millis(STATUS_POLL_INTERVAL_MS));
+            let status = self.transaction_status(tx_hash);
+            if status.is_committed() || started.elapsed() >= timeout {
+                break status;
+            }
+        };
+        self.status_waiters.fetch_sub(1, Ordering::SeqCst);
+        status
+    }
+
+    /// Attachment anchored with `hash`, and its stored content if it matches the
+    /// anchored size. The store already drops content that doesn't match the hash.
+    fn attachment_content(
+        &self,
+        hash: &Hash,
+    ) -> Result<(Option<Attachment>, Option<Vec<u8>>), ApiError> {
+        let attachment = Schema::new(self.blockchain.snapshot()).attachments().get(hash);
+        let content = match attachment {
+            Some(ref attachment) => match self.blobs.get(hash)? {
+                Some(content) if content.len() as u64 == attachment.size => Some(content),
+                _ => None,
+            },
+            None => None,
+        };
+        Ok((attachment, content))
+    }
+
+    fn attachment_status(&self, hash: &Hash) -> Result<AttachmentStatus, ApiError> {
+        let (attachment, content) = self.attachment_content(hash)?;
+        Ok(AttachmentStatus {
+            hash: *hash,
+            attachment,
+            available: content.is_some(),
+        })
+    }
+
     fn handle_range_ballots(self, router: &mut Router) {
         let range_ballots = move |req: &mut Request| -> IronResult<Response> {
             let limit = self.required_param::<usize>(req, "limit")?;
@@ -119,6 +900,40 @@ impl PublicApi {
         router.get("/v1/ballots", range_ballots, "range_ballots");
     }
 
+    fn handle_ballot_page(self, router: &mut Router) {
+        let ballot_page = move |req: &mut Request| -> IronResult<Response> {
+            let query = self.ballot_query(req)?;
+            let page = self.ballot_page(&query)?;
+            self.ok_response(&serde_json::to_value(page).unwrap())
+        };
+
+        router.get("/v2/ballots", ballot_page, "ballot_page");
+    }
+
+    /// Served on `/v2` and, as first released, on `/v1` with the same paging.
+    fn handle_ballots_by_author(self, router: &mut Router) {
+        let ballots_by_author = |api: PublicApi| {
+            move |req: &mut Request| -> IronResult<Response> {
+                let author = api.url_fragment::<PublicKey>(req, "key")?;
+                let mut query = api.ballot_query(req)?;
+                query.author = Some(author);
+                let page = api.ballot_page(&query)?;
+                api.ok_response(&serde_json::to_value(page).unwrap())
+            }
+        };
+
+        router.get(
+            "/v1/authors/:key/ballots",
+            ballots_by_author(self.clone()),
+            "ballots_by_author_v1",
+        );
+        router.get(
+            "/v2/authors/:key/ballots",
+            ballots_by_author(self),
+            "ballots_by_author",
+        );
+    }
+
     fn handle_ballot_by_hash(self, router: &mut Router) {
         let ballot_by_hash = move |req: &mut Request| -> IronResult<Response> {
             let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
@@ -142,6 +957,246 @@ impl PublicApi {
             "votes_for_ballot",
         );
     }
+
+    fn handle_ballot_results(self, router: &mut Router) {
+        let ballot_results = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let results = self.ballot_results(&proposals_hash);
+            self.ok_response(&serde_json::to_value(results).unwrap())
+        };
+
+        router.get(
+            "/v1/ballots/:hash/results",
+            ballot_results,
+            "ballot_results",
+        );
+    }
+
+    fn handle_revisions(self, router: &mut Router) {
+        let revisions = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let schema = Schema::new(self.blockchain.snapshot());
+            let history: Option<RevisionHistory> = if schema
+                .ballot_data_by_proposals_hash()
+                .contains(&proposals_hash)
+            {
+                Some(schema.revision_history(&proposals_hash))
+            } else {
+                None
+            };
+            self.ok_response(&serde_json::to_value(history).unwrap())
+        };
+
+        router.get("/v1/ballots/:hash/revisions", revisions, "revisions");
+    }
+
+    fn handle_voter_history(self, router: &mut Router) {
+        let voter_history = move |req: &mut Request| -> IronResult<Response> {
+            let voter = self.url_fragment::<PublicKey>(req, "key")?;
+            let history = self.voter_history(&voter);
+            self.ok_response(&serde_json::to_value(history).unwrap())
+        };
+
+        router.get("/v1/voters/:key/votes", voter_history, "voter_history");
+    }
+
+    fn handle_voters(self, router: &mut Router) {
+        let voters = move |_: &mut Request| -> IronResult<Response> {
+            let voters = Schema::new(&self.blockchain.snapshot()).eligible_voters();
+            self.ok_response(&serde_json::to_value(voters).unwrap())
+        };
+
+        router.get("/v1/voters", voters, "voters");
+    }
+
+    fn handle_config(self, router: &mut Router) {
+        let config = move |_: &mut Request| -> IronResult<Response> {
+            let config = Schema::new(&self.blockchain.snapshot()).config();
+            self.ok_response(&serde_json::to_value(config).unwrap())
+        };
+
+        router.get("/v1/config", config, "config");
+    }
+
+    fn handle_config_proposal(self, router: &mut Router) {
+        let config_proposal = move |req: &mut Request| -> IronResult<Response> {
+            let config_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let proposal: Option<ConfigProposal> = Schema::new(&self.blockchain.snapshot())
+                .config_proposals()
+                .get(&config_hash);
+            self.ok_response(&serde_json::to_value(proposal).unwrap())
+        };
+
+        router.get(
+            "/v1/config/proposals/:hash",
+            config_proposal,
+            "config_proposal",
+        );
+    }
+
+    fn handle_transaction(self, router: &mut Router) {
+        let post_transaction = move |req: &mut Request| -> IronResult<Response> {
+            let signed_tx = match req.get::<bodyparser::Struct<SignedTransaction>>() {
+                Ok(Some(signed_tx)) => signed_tx,
+                Ok(None) => Err(ApiError::BadRequest("Empty request body".into()))?,
+                Err(e) => Err(ApiError::BadRequest(e.to_string()))?,
+            };
+
+            let tx = signed_tx.into_transaction()?;
+            if !tx.verify() {
+                Err(ApiError::BadRequest("Unable to verify transaction".into()))?
+            }
+            let tx_hash = tx.raw().hash();
+
+            self.channel.send(tx).map_err(ApiError::from)?;
+
+            let response = TransactionResponse { tx_hash };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post("/v1/transactions", post_transaction, "post_transaction");
+    }
+
+    fn handle_transaction_status(self, router: &mut Router) {
+        let transaction_status = move |req: &mut Request| -> IronResult<Response> {
+            let tx_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let wait_ms = self.optional_param::<u64>(req, "wait_ms")?.unwrap_or(0);
+            let status = self.wait_transaction_status(&tx_hash, wait_ms);
+            self.ok_response(&serde_json::to_value(status).unwrap())
+        };
+
+        router.get(
+            "/v1/transactions/:hash/status",
+            transaction_status,
+            "transaction_status",
+        );
+    }
+
+    fn handle_attachment(self, router: &mut Router) {
+        let attachment = move |req: &mut Request| -> IronResult<Response> {
+            let hash = self.url_fragment::<Hash>(req, "hash")?;
+            let (attachment, content) = match self.attachment_content(&hash)? {
+                (Some(attachment), Some(content)) => (attachment, content),
+                (Some(_), None) => Err(ApiError::NotFound(format!(
+                    "Content of attachment {} isn't stored",
+                    hash
+                )))?,
+                (None, _) => Err(ApiError::NotFound(format!("No proposal attaches {}", hash)))?,
+            };
+
+            let mut resp = Response::with((status::Ok, content));
+            let mime = attachment
+                .mime_type
+                .parse()
+                .unwrap_or_else(|_| "application/octet-stream".parse().unwrap());
+            resp.headers.set(ContentType(mime));
+            Ok(resp)
+        };
+
+        router.get("/v1/attachments/:hash", attachment, "attachment");
+    }
+
+    fn handle_attachment_status(self, router: &mut Router) {
+        let attachment_status = move |req: &mut Request| -> IronResult<Response> {
+            let hash = self.url_fragment::<Hash>(req, "hash")?;
+            let status = self.attachment_status(&hash)?;
+            self.ok_response(&serde_json::to_value(status).unwrap())
+        };
+
+        router.get(
+            "/v1/attachments/:hash/status",
+            attachment_status,
+            "attachment_status",
+        );
+    }
+
+    /// Content is only accepted for attachments already anchored on the chain, so the
+    /// store can't be filled with documents no ballot refers to. Uploads have to be
+    /// signed by an eligible voter, and the body is read up to the size the
+    /// `max_attachment_size` limit allows.
+    fn handle_upload_attachment(self, router: &mut Router) {
+        let upload_attachment = move |req: &mut Request| -> IronResult<Response> {
+            let snapshot = self.blockchain.snapshot();
+            let schema = Schema::new(&snapshot);
+            let max_len = schema
+                .limits()
+                .max_attachment_size
+                .saturating_mul(2)
+                .saturating_add(MAX_UPLOAD_OVERHEAD);
+            let mut body = Vec::new();
+            req.body
+                .by_ref()
+                .take(max_len.saturating_add(1))
+                .read_to_end(&mut body)
+                .map_err(ApiError::from)?;
+            if body.is_empty() {
+                Err(ApiError::BadRequest("Empty request body".into()))?
+            }
+            if body.len() as u64 > max_len {
+                Err(ApiError::BadRequest(format!(
+                    "Upload is longer than {} bytes",
+                    max_len
+                )))?
+            }
+            let upload: AttachmentUpload = serde_json::from_slice(&body)
+                .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            let content = Vec::<u8>::from_hex(&upload.content_hex)
+                .map_err(|e| ApiError::BadRequest(format!("Invalid content_hex: {}", e)))?;
+
+            let hash = crypto::hash(&content);
+            if !crypto::verify(&upload.signature, hash.as_ref(), &upload.from)
+                || !schema.eligible_voters().contains(&upload.from)
+            {
+                Err(ApiError::Unauthorized)?
+            }
+            let attachment = schema
+                .attachments()
+                .get(&hash)
+                .ok_or_else(|| ApiError::NotFound(format!("No proposal attaches {}", hash)))?;
+            if content.len() as u64 != attachment.size {
+                Err(ApiError::BadRequest(format!(
+                    "Attachment {} is {} bytes long, not {}",
+                    hash,
+                    attachment.size,
+                    content.len()
+                )))?
+            }
+            self.blobs.put(&content).map_err(ApiError::from)?;
+
+            let status = self.attachment_status(&hash)?;
+            self.ok_response(&serde_json::to_value(status).unwrap())
+        };
+
+        router.post("/v1/attachments", upload_attachment, "upload_attachment");
+    }
+
+    fn handle_events(self, router: &mut Router) {
+        let events = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.optional_param::<Hash>(req, "proposals_hash")?;
+            let stream = match self.events.open_stream(proposals_hash) {
+                Some(stream) => stream,
+                None => {
+                    let mut body = serde_json::Map::new();
+                    body.insert(
+                        "description".to_string(),
+                        format!("At most {} event streams are served", MAX_EVENT_STREAMS).into(),
+                    );
+                    let body = serde_json::to_string_pretty(&body).unwrap();
+                    let mut resp = Response::with((status::ServiceUnavailable, body));
+                    resp.headers.set(ContentType::json());
+                    return Ok(resp);
+                }
+            };
+
+            let mut resp = Response::with(status::Ok);
+            resp.headers
+                .set(ContentType("text/event-stream".parse().unwrap()));
+            resp.body = Some(Box::new(stream));
+            Ok(resp)
+        };
+
+        router.get("/v1/events", events, "events");
+    }
 }
 
 impl PrivateApi {
@@ -206,13 +1261,188 @@ impl PrivateApi {
 
         router.post("/v1/ballots/:hash/postvote", post_vote, "post_vote");
     }
+
+    fn handle_answers(self, router: &mut Router) {
+        let post_answers = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let answers = match req.get::<bodyparser::Struct<Vec<Answer>>>() {
+                Ok(Some(answers)) => answers,
+                Ok(None) => Err(ApiError::BadRequest("Empty request body".into()))?,
+                Err(e) => Err(ApiError::BadRequest(e.to_string()))?,
+            };
+
+            let vote = VoteQuestions::new(
+                &self.service_keys.0,
+                &proposals_hash,
+                &serde_json::to_string(&answers).unwrap(),
+                &self.service_keys.1,
+            );
+            let tx_hash = vote.hash();
+
+            self.channel.send(vote.into()).map_err(ApiError::from)?;
+
+            let response = VoteResponse { tx_hash };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post("/v1/ballots/:hash/answers", post_answers, "post_answers");
+    }
+
+    fn handle_finalize(self, router: &mut Router) {
+        let finalize = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let finalize =
+                FinalizeBallot::new(&self.service_keys.0, &proposals_hash, &self.service_keys.1);
+            let tx_hash = finalize.hash();
+
+            self.channel.send(finalize.into()).map_err(ApiError::from)?;
+
+            let response = TransactionResponse { tx_hash };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post("/v1/ballots/:hash/finalize", finalize, "finalize");
+    }
+
+    fn handle_sponsor(self, router: &mut Router) {
+        let sponsor = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let sponsor =
+                SponsorBallot::new(&self.service_keys.0, &proposals_hash, &self.service_keys.1);
+            let tx_hash = sponsor.hash();
+
+            self.channel.send(sponsor.into()).map_err(ApiError::from)?;
+
+            let response = TransactionResponse { tx_hash };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post("/v1/ballots/:hash/sponsor", sponsor, "sponsor");
+    }
+
+    fn handle_amend(self, router: &mut Router) {
+        let amend = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let amendment = match req.get::<bodyparser::Struct<Amendment>>() {
+                Ok(Some(amendment)) => amendment,
+                Ok(None) => Err(ApiError::BadRequest("Empty request body".into()))?,
+                Err(e) => Err(ApiError::BadRequest(e.to_string()))?,
+            };
+
+            let amend = AmendBallot::new(
+                &self.service_keys.0,
+                &proposals_hash,
+                &serde_json::to_string(&amendment).unwrap(),
+                &self.service_keys.1,
+            );
+            let tx_hash = amend.hash();
+
+            self.channel.send(amend.into()).map_err(ApiError::from)?;
+
+            let response = TransactionResponse { tx_hash };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post("/v1/ballots/:hash/amend", amend, "amend");
+    }
+
+    fn handle_nominate(self, router: &mut Router) {
+        let nominate = move |req: &mut Request| -> IronResult<Response> {
+            let proposals_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let nomination = match req.get::<bodyparser::Struct<NominationRequest>>() {
+                Ok(Some(nomination)) => nomination,
+                Ok(None) => Err(ApiError::BadRequest("Empty request body".into()))?,
+                Err(e) => Err(ApiError::BadRequest(e.to_string()))?,
+            };
+
+            let nominate = Nominate::new(
+                &self.service_keys.0,
+                &proposals_hash,
+                &nomination.subject,
+                &nomination.description,
+                &self.service_keys.1,
+            );
+            let tx_hash = nominate.hash();
+
+            self.channel.send(nominate.into()).map_err(ApiError::from)?;
+
+            let response = TransactionResponse { tx_hash };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post("/v1/ballots/:hash/nominate", nominate, "nominate");
+    }
+
+    fn handle_propose_config(self, router: &mut Router) {
+        let propose_config = move |req: &mut Request| -> IronResult<Response> {
+            let config = match req.get::<bodyparser::Struct<BallotConfig>>() {
+                Ok(Some(config)) => config,
+                Ok(None) => Err(ApiError::BadRequest("Empty request body".into()))?,
+                Err(e) => Err(ApiError::BadRequest(e.to_string()))?,
+            };
+
+            let config_hash = config.hash();
+            let propose = ProposeConfig::new(
+                &self.service_keys.0,
+                &serde_json::to_string(&config).unwrap(),
+                &self.service_keys.1,
+            );
+            let tx_hash = propose.hash();
+
+            self.channel.send(propose.into()).map_err(ApiError::from)?;
+
+            let response = ConfigResponse {
+                tx_hash,
+                config_hash,
+            };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post("/v1/config/proposals", propose_config, "propose_config");
+    }
+
+    fn handle_vote_config(self, router: &mut Router) {
+        let vote_config = move |req: &mut Request| -> IronResult<Response> {
+            let config_hash = self.url_fragment::<Hash>(req, "hash")?;
+            let vote = VoteConfig::new(&self.service_keys.0, &config_hash, &self.service_keys.1);
+            let tx_hash = vote.hash();
+
+            self.channel.send(vote.into()).map_err(ApiError::from)?;
+
+            let response = ConfigResponse {
+                tx_hash,
+                config_hash,
+            };
+            self.ok_response(&serde_json::to_value(response).unwrap())
+        };
+
+        router.post(
+            "/v1/config/proposals/:hash/vote",
+            vote_config,
+            "vote_config",
+        );
+    }
 }
 
 impl ExonumApi for PublicApi {
     fn wire(&self, router: &mut Router) {
         self.clone().handle_range_ballots(router);
+        self.clone().handle_ballot_page(router);
+        self.clone().handle_ballots_by_author(router);
         self.clone().handle_ballot_by_hash(router);
         self.clone().handle_votes_for_ballot(router);
+        self.clone().handle_ballot_results(router);
+        self.clone().handle_revisions(router);
+        self.clone().handle_voter_history(router);
+        self.clone().handle_voters(router);
+        self.clone().handle_config(router);
+        self.clone().handle_config_proposal(router);
+        self.clone().handle_transaction(router);
+        self.clone().handle_transaction_status(router);
+        self.clone().handle_attachment(router);
+        self.clone().handle_attachment_status(router);
+        self.clone().handle_upload_attachment(router);
+        self.clone().handle_events(router);
     }
 }
 
@@ -220,5 +1450,12 @@ impl ExonumApi for PrivateApi {
     fn wire(&self, router: &mut Router) {
         self.clone().handle_ballot(router);
         self.clone().handle_vote(router);
+        self.clone().handle_answers(router);
+        self.clone().handle_finalize(router);
+        self.clone().handle_sponsor(router);
+        self.clone().handle_amend(router);
+        self.clone().handle_nominate(router);
+        self.clone().handle_propose_config(router);
+        self.clone().handle_vote_config(router);
     }
 }
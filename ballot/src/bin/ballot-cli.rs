@@ -0,0 +1,680 @@
+extern crate ballot;
+#[macro_use]
+extern crate clap;
+extern crate exonum;
+extern crate serde;
+extern crate serde_json;
+#[macro_use]
+extern crate serde_derive;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use ballot::client::{BallotClient, ClientError, HttpTransport};
+use ballot::{Amendment, Answer, Attachment, BallotHashInfo, BallotOrder, BallotQuery, BallotStatus,
+             NominationRequest, Outcome, ProposalList, VoteRequest};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use exonum::crypto::{self, Hash, PublicKey, SecretKey};
+use serde::Serialize;
+
+const DEFAULT_NODE: &str = "http://127.0.0.1:8200/api/services/ballot";
+const PAGE_SIZE: usize = 100;
+
+/// json example:
+/// {"public_key": "...", "secret_key": "..."}
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyFile {
+    public_key: PublicKey,
+    secret_key: SecretKey,
+}
+
+impl KeyFile {
+    fn load<P: AsRef<Path>>(path: P) -> Result<KeyFile, String> {
+        let file = File::open(path.as_ref()).map_err(|e| {
+            format!("Unable to open keyfile {}: {}", path.as_ref().display(), e)
+        })?;
+        serde_json::from_reader(file).map_err(|e| format!("Invalid keyfile: {}", e))
+    }
+
+    fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let file = File::create(path.as_ref()).map_err(|e| {
+            format!("Unable to create keyfile {}: {}", path.as_ref().display(), e)
+        })?;
+        serde_json::to_writer_pretty(file, self).map_err(|e| e.to_string())
+    }
+}
+
+fn client(matches: &ArgMatches) -> BallotClient<HttpTransport> {
+    let node = matches.value_of("node").unwrap_or(DEFAULT_NODE);
+    BallotClient::new(HttpTransport::new(node))
+}
+
+fn signing_client(matches: &ArgMatches) -> Result<BallotClient<HttpTransport>, String> {
+    let node = matches.value_of("node").unwrap_or(DEFAULT_NODE);
+    let keys = KeyFile::load(matches.value_of("keyfile").unwrap())?;
+    Ok(BallotClient::with_keys(
+        HttpTransport::new(node),
+        keys.public_key,
+        keys.secret_key,
+    ))
+}
+
+fn proposals_hash(matches: &ArgMatches) -> Result<Hash, String> {
+    value_t!(matches, "hash", Hash).map_err(|e| e.to_string())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn client_error(e: ClientError) -> String {
+    e.to_string()
+}
+
+fn keygen(matches: &ArgMatches) -> Result<(), String> {
+    let (public_key, secret_key) = crypto::gen_keypair();
+    let keys = KeyFile {
+        public_key,
+        secret_key,
+    };
+    keys.save(matches.value_of("output").unwrap())?;
+    println!("{}", keys.public_key);
+    Ok(())
+}
+
+fn create_ballot(matches: &ArgMatches) -> Result<(), String> {
+    let path = matches.value_of("proposals").unwrap();
+    let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path, e))?;
+    let proposals: ProposalList =
+        serde_json::from_reader(file).map_err(|e| format!("Invalid proposals: {}", e))?;
+
+    let resp = signing_client(matches)?
+        .create_ballot(&proposals)
+        .map_err(client_error)?;
+    print_json(&resp)
+}
+
+fn amend(matches: &ArgMatches) -> Result<(), String> {
+    let path = matches.value_of("amendment").unwrap();
+    let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path, e))?;
+    let amendment: Amendment =
+        serde_json::from_reader(file).map_err(|e| format!("Invalid amendment: {}", e))?;
+
+    let resp = signing_client(matches)?
+        .amend(&proposals_hash(matches)?, &amendment)
+        .map_err(client_error)?;
+    print_json(&resp)
+}
+
+fn nominate(matches: &ArgMatches) -> Result<(), String> {
+    let nomination = NominationRequest {
+        subject: matches.value_of("subject").unwrap().to_string(),
+        description: matches.value_of("description").unwrap_or("").to_string(),
+    };
+
+    let resp = signing_client(matches)?
+        .nominate(&proposals_hash(matches)?, &nomination)
+        .map_err(client_error)?;
+    print_json(&resp)
+}
+
+fn vote(matches: &ArgMatches) -> Result<(), String> {
+    let proposals_hash = proposals_hash(matches)?;
+    let vote_req = VoteRequest {
+        proposal_id: value_t!(matches, "proposal-id", u64).map_err(|e| e.to_string())?,
+        proposal_subject: matches.value_of("subject").unwrap().to_string(),
+    };
+
+    let resp = signing_client(matches)?
+        .vote(&proposals_hash, &vote_req)
+        .map_err(client_error)?;
+    print_json(&resp)
+}
+
+fn answer(matches: &ArgMatches) -> Result<(), String> {
+    let path = matches.value_of("answers").unwrap();
+    let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path, e))?;
+    let answers: Vec<Answer> =
+        serde_json::from_reader(file).map_err(|e| format!("Invalid answers: {}", e))?;
+
+    let resp = signing_client(matches)?
+        .answer(&proposals_hash(matches)?, &answers)
+        .map_err(client_error)?;
+    print_json(&resp)
+}
+
+fn read_content(path: &str) -> Result<Vec<u8>, String> {
+    let mut content = Vec::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut content))
+        .map_err(|e| format!("Unable to read {}: {}", path, e))?;
+    Ok(content)
+}
+
+/// Prints the attachment json of a document, to be added to a proposal before posting.
+fn attachment(matches: &ArgMatches) -> Result<(), String> {
+    let content = read_content(matches.value_of("file").unwrap())?;
+    let attachment = Attachment {
+        hash: crypto::hash(&content),
+        size: content.len() as u64,
+        mime_type: matches.value_of("mime-type").unwrap().to_string(),
+    };
+    print_json(&attachment)
+}
+
+fn upload(matches: &ArgMatches) -> Result<(), String> {
+    let content = read_content(matches.value_of("file").unwrap())?;
+    let status = signing_client(matches)?
+        .upload_attachment(&content)
+        .map_err(client_error)?;
+    print_json(&status)
+}
+
+fn list(matches: &ArgMatches) -> Result<(), String> {
+    let query = BallotQuery {
+        cursor: matches.value_of("cursor").map(String::from),
+        limit: Some(value_or(matches, "limit", PAGE_SIZE)),
+        order: if matches.is_present("newest") {
+            Some(BallotOrder::Newest)
+        } else {
+            None
+        },
+        status: optional_value(matches, "status")?,
+        author: optional_value(matches, "author")?,
+        not_voted_by: optional_value(matches, "not-voted-by")?,
+        category: matches.value_of("category").map(String::from),
+        tag: matches.value_of("tag").map(String::from),
+        ..Default::default()
+    };
+
+    let page = client(matches).ballots(&query).map_err(client_error)?;
+    for info in page.ballots {
+        println!("{}", summary(&info));
+    }
+    if let Some(cursor) = page.next_cursor {
+        println!("next cursor: {}", cursor);
+    }
+    Ok(())
+}
+
+/// Value of `name`, `default` if it's missing. Exits with the usage error on an
+/// invalid value.
+fn value_or<T>(matches: &ArgMatches, name: &str, default: T) -> T
+where
+    T: ::std::str::FromStr,
+    T::Err: ::std::fmt::Display,
+{
+    if !matches.is_present(name) {
+        return default;
+    }
+    value_t!(matches, name, T).unwrap_or_else(|e| e.exit())
+}
+
+fn optional_value<T>(matches: &ArgMatches, name: &str) -> Result<Option<T>, String>
+where
+    T: ::std::str::FromStr,
+    T::Err: ::std::fmt::Display,
+{
+    match matches.value_of(name) {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|e| format!("Invalid {}: {}", name, e)),
+        None => Ok(None),
+    }
+}
+
+fn show(matches: &ArgMatches) -> Result<(), String> {
+    let info = client(matches)
+        .ballot(&proposals_hash(matches)?)
+        .map_err(client_error)?;
+    print_json(&info)
+}
+
+fn tally(matches: &ArgMatches) -> Result<(), String> {
+    let proposals_hash = proposals_hash(matches)?;
+    let results = client(matches)
+        .results(&proposals_hash)
+        .map_err(client_error)?
+        .ok_or_else(|| format!("Ballot {} doesn't exist", proposals_hash))?;
+
+    println!(
+        "ballot {} deadline {} {:?}",
+        results.proposals_hash, results.deadline, results.status
+    );
+    for proposal in &results.proposals {
+        println!("{:>6} {:>4} {}", proposal.votes, proposal.id, proposal.subject);
+    }
+    println!(
+        "{} of {} votes cast, quorum {} {}",
+        results.votes_cast,
+        results.electorate,
+        results.quorum,
+        if results.quorum_reached {
+            "reached"
+        } else {
+            "not reached"
+        }
+    );
+    if results.questions.is_empty() {
+        print_outcome(&results.outcome);
+    }
+    for question in &results.questions {
+        println!("question {} {:?}: {}", question.id, question.mode, question.subject);
+        for proposal in &question.proposals {
+            println!("{:>6} {:>4} {}", proposal.votes, proposal.id, proposal.subject);
+        }
+        print_outcome(&question.outcome);
+    }
+    if let Some(sponsorship) = results.sponsorship {
+        match sponsorship.opened_at {
+            Some(height) => println!(
+                "opened at {} by {} sponsors",
+                height,
+                sponsorship.sponsors.len()
+            ),
+            None => println!(
+                "seeking sponsors, {} of {}",
+                sponsorship.sponsors.len(),
+                sponsorship.min_sponsors
+            ),
+        }
+    }
+    if let Some(finalization) = results.finalization {
+        match finalization.action {
+            Some(action) => println!(
+                "finalized at {}, applied {}",
+                finalization.height,
+                serde_json::to_string(&action).map_err(|e| e.to_string())?
+            ),
+            None => println!("finalized at {}, no action applied", finalization.height),
+        }
+    }
+    Ok(())
+}
+
+fn print_outcome(outcome: &Outcome) {
+    match *outcome {
+        Outcome::NoVotes => println!("no votes"),
+        Outcome::Winner { proposal_id } => println!("winner: {}", proposal_id),
+        Outcome::Tie { ref proposal_ids } => println!("tie: {:?}", proposal_ids),
+    }
+}
+
+fn finalize(matches: &ArgMatches) -> Result<(), String> {
+    let resp = signing_client(matches)?
+        .finalize(&proposals_hash(matches)?)
+        .map_err(client_error)?;
+    print_json(&resp)
+}
+
+fn sponsor(matches: &ArgMatches) -> Result<(), String> {
+    let resp = signing_client(matches)?
+        .sponsor(&proposals_hash(matches)?)
+        .map_err(client_error)?;
+    print_json(&resp)
+}
+
+fn voter(matches: &ArgMatches) -> Result<(), String> {
+    let voter = value_t!(matches, "key", PublicKey).map_err(|e| e.to_string())?;
+    let history = client(matches)
+        .voter_history(&voter)
+        .map_err(client_error)?;
+
+    for record in &history.votes {
+        let vote = record.tx_vote();
+        println!(
+            "{} height {} proposal {} {}",
+            vote.proposals_hash(),
+            record.height(),
+            vote.proposal_id(),
+            vote.proposal_subject()
+        );
+    }
+    for record in &history.answers {
+        println!(
+            "{} height {} answered {} questions",
+            record.proposals_hash,
+            record.answer_sheet.height,
+            record.answer_sheet.answers.len()
+        );
+    }
+    let stats = &history.stats;
+    println!(
+        "{} votes cast, {} of {} closed ballots voted",
+        stats.votes_cast, stats.closed_voted, stats.ballots_closed
+    );
+    if let Some(rate) = stats.participation_rate {
+        println!("participation {:.1}%", rate * 100.0);
+    }
+    Ok(())
+}
+
+fn watch(matches: &ArgMatches) -> Result<(), String> {
+    let interval = value_or(matches, "interval", 5);
+    let client = client(matches);
+
+    // The first poll only records the ballots already open.
+    let mut first_poll = true;
+    let mut votes_cast = BTreeMap::new();
+    loop {
+        let mut query = BallotQuery {
+            limit: Some(PAGE_SIZE),
+            status: Some(BallotStatus::Open),
+            ..Default::default()
+        };
+        loop {
+            let page = client.ballots(&query).map_err(client_error)?;
+            for info in &page.ballots {
+                let change = track_votes(&mut votes_cast, info.proposals_hash, cast(info));
+                match change {
+                    _ if first_poll => (),
+                    Some(BallotChange::New) => println!("new ballot: {}", summary(info)),
+                    Some(BallotChange::VotesChanged) => {
+                        println!("votes changed: {}", summary(info))
+                    }
+                    None => (),
+                }
+            }
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        if first_poll {
+            println!("watching {} open ballots", votes_cast.len());
+            first_poll = false;
+        }
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+/// Change of an open ballot since the previous poll of `watch`.
+#[derive(Debug, PartialEq)]
+enum BallotChange {
+    New,
+    VotesChanged,
+}
+
+/// Records the votes cast on a ballot and tells how it changed since they were last
+/// recorded.
+fn track_votes(
+    votes_cast: &mut BTreeMap<Hash, usize>,
+    proposals_hash: Hash,
+    cast: usize,
+) -> Option<BallotChange> {
+    match votes_cast.insert(proposals_hash, cast) {
+        None => Some(BallotChange::New),
+        Some(previous) if previous != cast => Some(BallotChange::VotesChanged),
+        Some(_) => None,
+    }
+}
+
+fn cast(info: &BallotHashInfo) -> usize {
+    info.votes
+        .as_ref()
+        .map_or(0, |votes| votes.iter().filter(|vote| vote.is_some()).count())
+}
+
+fn summary(info: &BallotHashInfo) -> String {
+    let deadline = info.proposals
+        .as_ref()
+        .map_or(0, |proposals| proposals.deadline());
+    let total = info.votes.as_ref().map_or(0, |votes| votes.len());
+    let title = info.proposals
+        .as_ref()
+        .and_then(|proposals| proposals.title())
+        .map_or(String::new(), |title| format!(" {}", title));
+    format!(
+        "{} deadline {} votes {}/{}{}",
+        info.proposals_hash, deadline, cast(info), total, title
+    )
+}
+
+fn app() -> App<'static, 'static> {
+    let node = Arg::with_name("node")
+        .long("node")
+        .takes_value(true)
+        .help("Service public api root");
+    let keyfile = Arg::with_name("keyfile")
+        .long("keyfile")
+        .short("k")
+        .takes_value(true)
+        .required(true)
+        .help("Keyfile generated by keygen");
+    let hash = Arg::with_name("hash")
+        .required(true)
+        .help("Proposals hash of the ballot");
+
+    App::new("ballot-cli")
+        .about("Ballot service command line client")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("keygen")
+                .about("Generates a keypair into a keyfile")
+                .arg(Arg::with_name("output").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("create-ballot")
+                .about("Signs and posts a ballot from a proposals json file")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(Arg::with_name("proposals").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("amend")
+                .about("Signs and posts an amendment json file of a ballot in discussion")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(hash.clone())
+                .arg(Arg::with_name("amendment").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("nominate")
+                .about("Signs and posts a write-in proposal of a ballot accepting nominations")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(hash.clone())
+                .arg(Arg::with_name("subject").required(true))
+                .arg(Arg::with_name("description")),
+        )
+        .subcommand(
+            SubCommand::with_name("vote")
+                .about("Signs and posts a vote")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(hash.clone())
+                .arg(Arg::with_name("proposal-id").required(true))
+                .arg(Arg::with_name("subject").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("answer")
+                .about("Signs and posts an answers json file of a ballot with questions")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(hash.clone())
+                .arg(Arg::with_name("answers").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("attachment")
+                .about("Prints the attachment json of a document for a proposal")
+                .arg(Arg::with_name("file").required(true))
+                .arg(
+                    Arg::with_name("mime-type")
+                        .long("mime-type")
+                        .takes_value(true)
+                        .default_value("application/octet-stream"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("upload")
+                .about("Signs and uploads a document attached to a posted ballot")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(Arg::with_name("file").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("list")
+                .about("Lists ballots")
+                .arg(node.clone())
+                .arg(Arg::with_name("cursor").long("cursor").takes_value(true))
+                .arg(Arg::with_name("limit").long("limit").takes_value(true))
+                .arg(Arg::with_name("newest").long("newest"))
+                .arg(
+                    Arg::with_name("status")
+                        .long("status")
+                        .takes_value(true)
+                        .possible_values(&[
+                            "open",
+                            "closed",
+                            "seeking_sponsors",
+                            "in_discussion",
+                            "nominating",
+                        ]),
+                )
+                .arg(Arg::with_name("author").long("author").takes_value(true))
+                .arg(
+                    Arg::with_name("not-voted-by")
+                        .long("not-voted-by")
+                        .takes_value(true),
+                )
+                .arg(Arg::with_name("category").long("category").takes_value(true))
+                .arg(Arg::with_name("tag").long("tag").takes_value(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("show")
+                .about("Shows a ballot with its votes")
+                .arg(node.clone())
+                .arg(hash.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("tally")
+                .about("Shows counted results of a ballot")
+                .arg(node.clone())
+                .arg(hash.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("finalize")
+                .about("Signs and posts the finalization of a closed ballot")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(hash.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("sponsor")
+                .about("Signs and posts a sponsorship of a ballot seeking sponsors")
+                .arg(node.clone())
+                .arg(keyfile.clone())
+                .arg(hash.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("voter")
+                .about("Shows the voting record and participation of a key")
+                .arg(node.clone())
+                .arg(
+                    Arg::with_name("key")
+                        .required(true)
+                        .help("Service public key of the voter"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("watch")
+                .about("Prints new open ballots and votes as they appear")
+                .arg(node.clone())
+                .arg(
+                    Arg::with_name("interval")
+                        .long("interval")
+                        .takes_value(true)
+                        .help("Polling interval in seconds"),
+                ),
+        )
+}
+
+fn main() {
+    let matches = app().get_matches();
+    let result = match matches.subcommand() {
+        ("keygen", Some(matches)) => keygen(matches),
+        ("create-ballot", Some(matches)) => create_ballot(matches),
+        ("amend", Some(matches)) => amend(matches),
+        ("nominate", Some(matches)) => nominate(matches),
+        ("vote", Some(matches)) => vote(matches),
+        ("answer", Some(matches)) => answer(matches),
+        ("attachment", Some(matches)) => attachment(matches),
+        ("upload", Some(matches)) => upload(matches),
+        ("list", Some(matches)) => list(matches),
+        ("show", Some(matches)) => show(matches),
+        ("tally", Some(matches)) => tally(matches),
+        ("finalize", Some(matches)) => finalize(matches),
+        ("sponsor", Some(matches)) => sponsor(matches),
+        ("voter", Some(matches)) => voter(matches),
+        ("watch", Some(matches)) => watch(matches),
+        _ => unreachable!(),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use exonum::crypto;
+
+    use super::{app, track_votes, value_or, BallotChange, PAGE_SIZE};
+
+    #[test]
+    fn test_parse_list_limit() {
+        let matches = app()
+            .get_matches_from_safe(vec!["ballot-cli", "list", "--limit", "7"])
+            .unwrap();
+        let matches = matches.subcommand_matches("list").unwrap();
+        assert_eq!(7, value_or(matches, "limit", PAGE_SIZE));
+
+        let matches = app()
+            .get_matches_from_safe(vec!["ballot-cli", "list"])
+            .unwrap();
+        let matches = matches.subcommand_matches("list").unwrap();
+        assert_eq!(PAGE_SIZE, value_or(matches, "limit", PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_parse_requires_keyfile_to_sign() {
+        let hash = crypto::hash(&[1]).to_hex();
+        let args = vec!["ballot-cli", "vote", &hash, "1", "triss"];
+        assert!(app().get_matches_from_safe(args.clone()).is_err());
+
+        let mut args = args;
+        args.extend(&["--keyfile", "keys.json"]);
+        let matches = app().get_matches_from_safe(args).unwrap();
+        let matches = matches.subcommand_matches("vote").unwrap();
+        assert_eq!(Some("keys.json"), matches.value_of("keyfile"));
+        assert_eq!(Some("triss"), matches.value_of("subject"));
+    }
+
+    #[test]
+    fn test_track_votes() {
+        let mut votes_cast = BTreeMap::new();
+        let first = crypto::hash(&[1]);
+        let second = crypto::hash(&[2]);
+
+        assert_eq!(Some(BallotChange::New), track_votes(&mut votes_cast, first, 0));
+        assert_eq!(None, track_votes(&mut votes_cast, first, 0));
+        assert_eq!(
+            Some(BallotChange::VotesChanged),
+            track_votes(&mut votes_cast, first, 1)
+        );
+        assert_eq!(Some(BallotChange::New), track_votes(&mut votes_cast, second, 1));
+        assert_eq!(None, track_votes(&mut votes_cast, first, 1));
+    }
+}
@@ -0,0 +1,212 @@
+extern crate ballot;
+extern crate clap;
+extern crate exonum;
+#[cfg(feature = "validator-set")]
+extern crate exonum_configuration;
+extern crate failure;
+
+use std::env;
+use std::net::SocketAddr;
+use std::panic::{self, PanicInfo};
+use std::thread;
+
+use ballot::{BallotService, BlobStore};
+use clap::{App, Arg};
+use exonum::blockchain::Service;
+use exonum::helpers::{self, fabric};
+use exonum::node::Node;
+use exonum::storage::{DbOptions, MemoryDB, RocksDB};
+#[cfg(feature = "validator-set")]
+use exonum_configuration::Service as ConfigurationService;
+
+const RUN_TESTNET: &str = "run-testnet";
+const BLOB_DIR: &str = "BLOB_DIR";
+
+struct ServiceFactory;
+
+impl fabric::ServiceFactory for ServiceFactory {
+    fn command(&mut self, command: fabric::CommandName) -> Option<Box<fabric::CommandExtension>> {
+        if command == fabric::Run::name() {
+            Some(Box::new(BlobDirArgument))
+        } else {
+            None
+        }
+    }
+
+    fn make_service(&mut self, context: &fabric::Context) -> Box<Service> {
+        let blobs = blob_store(context.arg::<String>(BLOB_DIR).ok());
+        Box::new(BallotService::new().with_blob_store(blobs))
+    }
+}
+
+/// Adds `--blob-dir` to the `run` command.
+struct BlobDirArgument;
+
+impl fabric::CommandExtension for BlobDirArgument {
+    fn args(&self) -> Vec<fabric::Argument> {
+        vec![
+            fabric::Argument::new_named(
+                BLOB_DIR,
+                false,
+                "Keeps attachment contents in this directory instead of memory.",
+                None,
+                "blob-dir",
+                false,
+            ),
+        ]
+    }
+
+    fn execute(&self, context: fabric::Context) -> Result<fabric::Context, failure::Error> {
+        Ok(context)
+    }
+}
+
+fn blob_store(dir: Option<String>) -> BlobStore {
+    match dir {
+        Some(dir) => BlobStore::in_dir(dir).expect("Unable to open blob directory"),
+        None => BlobStore::in_memory(),
+    }
+}
+
+/// Runs `count` validators with the ballot service inside this process.
+///
+/// Node `i` listens for peers on `start_port + i`, serves the public api on
+/// `api_port + 2 * i` and the private api on `api_port + 2 * i + 1`.
+fn run_testnet() {
+    let matches = App::new(RUN_TESTNET)
+        .about("Runs a local network of validators in a single process")
+        .arg(
+            Arg::with_name("COUNT")
+                .required(true)
+                .help("Count of validators in testnet"),
+        )
+        .arg(
+            Arg::with_name("START_PORT")
+                .long("start")
+                .short("p")
+                .takes_value(true)
+                .help("Port number started from which validators listen"),
+        )
+        .arg(
+            Arg::with_name("API_PORT")
+                .long("api-port")
+                .takes_value(true)
+                .help("Port number started from which validators serve api"),
+        )
+        .arg(
+            Arg::with_name("DB_DIR")
+                .long("db-dir")
+                .takes_value(true)
+                .help("Keeps databases in this directory instead of memory"),
+        )
+        .arg(
+            Arg::with_name(BLOB_DIR)
+                .long("blob-dir")
+                .takes_value(true)
+                .help("Keeps attachment contents in this directory instead of memory"),
+        )
+        .get_matches_from(env::args().skip(1));
+
+    let count: u8 = matches
+        .value_of("COUNT")
+        .unwrap()
+        .parse()
+        .expect("count as int");
+    let start_port: u16 = matches
+        .value_of("START_PORT")
+        .map_or(2000, |port| port.parse().expect("start port as int"));
+    let api_port: u16 = matches
+        .value_of("API_PORT")
+        .map_or(8200, |port| port.parse().expect("api port as int"));
+    let db_dir = matches.value_of("DB_DIR").map(String::from);
+    let blob_dir = matches.value_of(BLOB_DIR).map(String::from);
+
+    if count == 0 {
+        panic!("Can't run testnet with zero nodes count.");
+    }
+
+    let nodes = helpers::generate_testnet_config(count, start_port)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, mut config)| {
+            let public_api_port = api_port + 2 * idx as u16;
+            config.api.public_api_address = Some(local_address(public_api_port));
+            config.api.private_api_address = Some(local_address(public_api_port + 1));
+            println!(
+                "node {}: public api {}, private api {}, service key {}",
+                idx,
+                public_api_port,
+                public_api_port + 1,
+                config.service_public_key
+            );
+
+            let blobs = blob_store(
+                blob_dir
+                    .as_ref()
+                    .map(|dir| format!("{}/node_{}", dir, idx)),
+            );
+            let ballot: Box<Service> = Box::new(BallotService::new().with_blob_store(blobs));
+            #[cfg(feature = "validator-set")]
+            let services = vec![Box::new(ConfigurationService::new()) as Box<Service>, ballot];
+            #[cfg(not(feature = "validator-set"))]
+            let services = vec![ballot];
+            let db_dir = db_dir.clone();
+            thread::spawn(move || {
+                let node = match db_dir {
+                    Some(dir) => {
+                        let path = format!("{}/node_{}", dir, idx);
+                        let db = RocksDB::open(&path, &DbOptions::default())
+                            .expect("Unable to open database");
+                        Node::new(db, services, config)
+                    }
+                    None => Node::new(MemoryDB::new(), services, config),
+                };
+                ballot::migrate(&mut node.blockchain()).expect("Unable to migrate database");
+                node.run().expect("Node return error")
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for node in nodes {
+        node.join().expect("Node thread panicked");
+    }
+}
+
+fn local_address(port: u16) -> SocketAddr {
+    format!("127.0.0.1:{}", port).parse().unwrap()
+}
+
+/// Prints command line errors without a backtrace, like the hook of `NodeBuilder::run`.
+fn panic_hook(info: &PanicInfo) {
+    let msg = match info.payload().downcast_ref::<&'static str>() {
+        Some(s) => *s,
+        None => match info.payload().downcast_ref::<String>() {
+            Some(s) => &s[..],
+            None => "Box<Any>",
+        },
+    };
+    println!("error: {}", msg);
+}
+
+fn main() {
+    helpers::init_logger().unwrap();
+
+    if env::args().nth(1).as_ref().map(String::as_str) == Some(RUN_TESTNET) {
+        run_testnet();
+    } else {
+        // Same as `NodeBuilder::run`, which can't be used as the database has to be
+        // migrated before the node starts.
+        let old_hook = panic::take_hook();
+        panic::set_hook(Box::new(panic_hook));
+        let builder = fabric::NodeBuilder::new();
+        #[cfg(feature = "validator-set")]
+        let builder = builder.with_service(Box::new(exonum_configuration::ServiceFactory));
+        let node = builder.with_service(Box::new(ServiceFactory)).parse_cmd();
+        panic::set_hook(old_hook);
+
+        if let Some(node) = node {
+            ballot::migrate(&mut node.blockchain()).expect("Unable to migrate database");
+            node.run().expect("Node return error")
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use exonum::crypto::{self, Hash};
+
+/// Contents of proposal attachments by content hash, kept by each node outside the
+/// blockchain. Only the hash, size and mime type of an attachment are posted.
+///
+/// Contents are hashed again on every read, a content that no longer matches its
+/// hash, e.g. a file changed on disk, is never returned.
+#[derive(Debug, Clone, Default)]
+pub struct BlobStore {
+    backend: Backend,
+}
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Memory(Arc<Mutex<HashMap<Hash, Vec<u8>>>>),
+    Dir(PathBuf),
+}
+
+impl Default for Backend {
+    fn default() -> Backend {
+        Backend::Memory(Arc::default())
+    }
+}
+
+impl BlobStore {
+    /// Store kept in memory, its contents are lost when the node stops.
+    pub fn in_memory() -> Self {
+        BlobStore::default()
+    }
+
+    /// Store keeping each content in a file of `dir` named by the hex content hash,
+    /// the directory is created if missing.
+    pub fn in_dir<P: Into<PathBuf>>(dir: P) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(BlobStore {
+            backend: Backend::Dir(dir),
+        })
+    }
+
+    /// Stores `content` and returns its hash.
+    pub fn put(&self, content: &[u8]) -> io::Result<Hash> {
+        let hash = crypto::hash(content);
+        match self.backend {
+            Backend::Memory(ref blobs) => {
+                blobs.lock().unwrap().insert(hash, content.to_vec());
+            }
+            Backend::Dir(ref dir) => {
+                // Renamed once written, so a failed write never leaves a partial
+                // content under the hash. Each write has its own file, concurrent
+                // uploads of one content don't write into each other.
+                let partial = partial_path(dir, &hash);
+                let written = File::create(&partial).and_then(|mut file| file.write_all(content));
+                if let Err(e) = written.and_then(|_| fs::rename(&partial, blob_path(dir, &hash))) {
+                    let _ = fs::remove_file(&partial);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(hash)
+    }
+
+    /// Content stored under `hash`, `None` if it's missing or doesn't match the hash.
+    pub fn get(&self, hash: &Hash) -> io::Result<Option<Vec<u8>>> {
+        let content = match self.backend {
+            Backend::Memory(ref blobs) => blobs.lock().unwrap().get(hash).cloned(),
+            Backend::Dir(ref dir) => match File::open(blob_path(dir, hash)) {
+                Ok(mut file) => {
+                    let mut content = Vec::new();
+                    file.read_to_end(&mut content)?;
+                    Some(content)
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e),
+            },
+        };
+
+        match content {
+            Some(ref content) if crypto::hash(content) != *hash => Ok(None),
+            content => Ok(content),
+        }
+    }
+}
+
+fn blob_path(dir: &Path, hash: &Hash) -> PathBuf {
+    dir.join(hash.to_hex())
+}
+
+/// Temporary file for a write of `hash`, unique among the writes of all processes
+/// sharing `dir`.
+fn partial_path(dir: &Path, hash: &Hash) -> PathBuf {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+    let write = WRITES.fetch_add(1, Ordering::SeqCst);
+    dir.join(format!("{}.{}.{}.partial", hash.to_hex(), process::id(), write))
+}
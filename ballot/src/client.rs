@@ -0,0 +1,502 @@
+use std::io::Read;
+
+use exonum::crypto::{self, CryptoHash, Hash, PublicKey, SecretKey};
+use exonum::encoding::serialize::encode_hex;
+use exonum::storage::StorageValue;
+use hyper::Client as HttpClient;
+use hyper::header::ContentType;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Error as JsonError};
+
+use api::{AttachmentStatus, AttachmentUpload, BallotHashInfo, BallotPage, BallotQuery,
+          BallotResponse, BallotResults, ConfigResponse, NominationRequest, SignedTransaction,
+          TransactionResponse, TransactionStatus, VoteRequest, VoteResponse, VoterHistory,
+          VotesInfo};
+use schema::{Amendment, Answer, BallotConfig, ConfigProposal, ProposalList, RevisionHistory};
+use transactions::{AmendBallot, Ballot, FinalizeBallot, Nominate, ProposeConfig, SponsorBallot,
+                   Transactions as BallotTransactions, Vote, VoteConfig, VoteQuestions};
+
+#[derive(Debug, Fail)]
+pub enum ClientError {
+    #[fail(display = "Http error: {}", _0)]
+    Http(#[cause] ::hyper::Error),
+
+    #[fail(display = "Io error: {}", _0)]
+    Io(#[cause] ::std::io::Error),
+
+    #[fail(display = "Invalid json: {}", _0)]
+    Json(#[cause] JsonError),
+
+    #[fail(display = "Unexpected response status {}: {}", _0, _1)]
+    Status(u16, String),
+
+    #[fail(display = "Client keys are required to sign transactions")]
+    MissingKeys,
+}
+
+impl From<::hyper::Error> for ClientError {
+    fn from(e: ::hyper::Error) -> ClientError {
+        ClientError::Http(e)
+    }
+}
+
+impl From<::std::io::Error> for ClientError {
+    fn from(e: ::std::io::Error) -> ClientError {
+        ClientError::Io(e)
+    }
+}
+
+impl From<JsonError> for ClientError {
+    fn from(e: JsonError) -> ClientError {
+        ClientError::Json(e)
+    }
+}
+
+/// Transport used by `BallotClient` to reach the service public api.
+///
+/// Endpoints are relative to the service api root, e.g. `/v1/ballots`.
+pub trait Transport {
+    fn get<D: DeserializeOwned>(&self, endpoint: &str) -> Result<D, ClientError>;
+
+    fn post<T: Serialize, D: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body: &T,
+    ) -> Result<D, ClientError>;
+}
+
+/// Transport over http, `base_url` example:
+/// http://127.0.0.1:8200/api/services/ballot
+pub struct HttpTransport {
+    base_url: String,
+    client: HttpClient,
+}
+
+impl HttpTransport {
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        HttpTransport {
+            base_url: base_url.into().trim_right_matches('/').to_string(),
+            client: HttpClient::new(),
+        }
+    }
+
+    fn read_response<D: DeserializeOwned>(
+        mut response: ::hyper::client::Response,
+    ) -> Result<D, ClientError> {
+        let mut body = String::new();
+        response.read_to_string(&mut body)?;
+
+        if !response.status.is_success() {
+            Err(ClientError::Status(response.status.to_u16(), body))?
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+impl Transport for HttpTransport {
+    fn get<D: DeserializeOwned>(&self, endpoint: &str) -> Result<D, ClientError> {
+        let url = format!("{}{}", self.base_url, endpoint);
+        let response = self.client.get(&url).send()?;
+        Self::read_response(response)
+    }
+
+    fn post<T: Serialize, D: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body: &T,
+    ) -> Result<D, ClientError> {
+        let url = format!("{}{}", self.base_url, endpoint);
+        let body = serde_json::to_string(body)?;
+        let response = self.client
+            .post(&url)
+            .header(ContentType::json())
+            .body(body.as_str())
+            .send()?;
+        Self::read_response(response)
+    }
+}
+
+/// In-process transport, requests are handled by the testkit's api without networking.
+///
+/// Error responses are returned as `ClientError::Status` like over http.
+#[cfg(any(test, feature = "testkit"))]
+impl Transport for ::exonum_testkit::TestKitApi {
+    fn get<D: DeserializeOwned>(&self, endpoint: &str) -> Result<D, ClientError> {
+        use iron::Headers;
+        use iron_test::request;
+
+        let response = request::get(&testkit_url(endpoint), Headers::new(), self.public_mount());
+        read_testkit_response(response)
+    }
+
+    fn post<T: Serialize, D: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body: &T,
+    ) -> Result<D, ClientError> {
+        use iron::Headers;
+        use iron_test::request;
+
+        let mut headers = Headers::new();
+        headers.set(ContentType::json());
+        let body = serde_json::to_string(body)?;
+        let response = request::post(&testkit_url(endpoint), headers, &body, self.public_mount());
+        read_testkit_response(response)
+    }
+}
+
+#[cfg(any(test, feature = "testkit"))]
+fn testkit_url(endpoint: &str) -> String {
+    use SERVICE_NAME;
+
+    format!("http://localhost:3000/api/services/{}{}", SERVICE_NAME, endpoint)
+}
+
+#[cfg(any(test, feature = "testkit"))]
+fn read_testkit_response<D: DeserializeOwned>(
+    response: ::iron::IronResult<::iron::Response>,
+) -> Result<D, ClientError> {
+    use iron_test::response::extract_body_to_string;
+
+    let response = response.unwrap_or_else(|e| e.response);
+    let status = response.status.map(|status| status.to_u16()).unwrap_or(500);
+    let body = extract_body_to_string(response);
+
+    if status >= 300 {
+        Err(ClientError::Status(status, body))?
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Typed client for the ballot service public api.
+///
+/// Transactions are built and signed locally with the client keys, then submitted
+/// through `/v1/transactions`, so the keys never leave the client.
+pub struct BallotClient<T: Transport> {
+    transport: T,
+    keys: Option<(PublicKey, SecretKey)>,
+}
+
+impl<T: Transport> BallotClient<T> {
+    pub fn new(transport: T) -> Self {
+        BallotClient {
+            transport,
+            keys: None,
+        }
+    }
+
+    pub fn with_keys(transport: T, public_key: PublicKey, secret_key: SecretKey) -> Self {
+        BallotClient {
+            transport,
+            keys: Some((public_key, secret_key)),
+        }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Ballots by ordinal, from `offset` on.
+    pub fn range_ballots(
+        &self,
+        offset: u64,
+        limit: usize,
+    ) -> Result<Vec<BallotHashInfo>, ClientError> {
+        self.transport
+            .get(&format!("/v1/ballots?limit={}&offset={}", limit, offset))
+    }
+
+    pub fn ballots(&self, query: &BallotQuery) -> Result<BallotPage, ClientError> {
+        self.transport
+            .get(&format!("/v2/ballots{}", query.to_query_string()))
+    }
+
+    /// Ballots posted by `author`, the `author` field of `query` is ignored.
+    pub fn ballots_by_author(
+        &self,
+        author: &PublicKey,
+        query: &BallotQuery,
+    ) -> Result<BallotPage, ClientError> {
+        self.transport.get(&format!(
+            "/v2/authors/{}/ballots{}",
+            author,
+            query.to_query_string()
+        ))
+    }
+
+    pub fn ballot(&self, proposals_hash: &Hash) -> Result<BallotHashInfo, ClientError> {
+        self.transport
+            .get(&format!("/v1/ballots/{}", proposals_hash))
+    }
+
+    pub fn votes(&self, proposals_hash: &Hash) -> Result<VotesInfo, ClientError> {
+        self.transport
+            .get(&format!("/v1/ballots/{}/votes", proposals_hash))
+    }
+
+    /// Counted results of a ballot, `None` if the ballot doesn't exist.
+    pub fn results(&self, proposals_hash: &Hash) -> Result<Option<BallotResults>, ClientError> {
+        self.transport
+            .get(&format!("/v1/ballots/{}/results", proposals_hash))
+    }
+
+    /// Amended revisions of a ballot, `None` if the ballot doesn't exist.
+    pub fn revisions(&self, proposals_hash: &Hash) -> Result<Option<RevisionHistory>, ClientError> {
+        self.transport
+            .get(&format!("/v1/ballots/{}/revisions", proposals_hash))
+    }
+
+    /// Votes cast by `voter` across ballots with participation statistics.
+    pub fn voter_history(&self, voter: &PublicKey) -> Result<VoterHistory, ClientError> {
+        self.transport
+            .get(&format!("/v1/voters/{}/votes", voter))
+    }
+
+    /// Keys allowed to post ballots and to vote on new ones.
+    pub fn voters(&self) -> Result<Vec<PublicKey>, ClientError> {
+        self.transport.get("/v1/voters")
+    }
+
+    /// Configuration in effect.
+    pub fn config(&self) -> Result<BallotConfig, ClientError> {
+        self.transport.get("/v1/config")
+    }
+
+    /// Configuration proposal with its approvals, `None` if it wasn't proposed.
+    pub fn config_proposal(
+        &self,
+        config_hash: &Hash,
+    ) -> Result<Option<ConfigProposal>, ClientError> {
+        self.transport
+            .get(&format!("/v1/config/proposals/{}", config_hash))
+    }
+
+    /// Anchored attachment with the content `hash` and whether the node serves it.
+    pub fn attachment_status(&self, hash: &Hash) -> Result<AttachmentStatus, ClientError> {
+        self.transport
+            .get(&format!("/v1/attachments/{}/status", hash))
+    }
+
+    /// Hands the content of an attachment to the node, which only takes content of
+    /// attachments posted in a committed ballot, signed by an eligible voter.
+    pub fn upload_attachment(&self, content: &[u8]) -> Result<AttachmentStatus, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        let upload = AttachmentUpload {
+            from: *public_key,
+            content_hex: encode_hex(content),
+            signature: crypto::sign(crypto::hash(content).as_ref(), secret_key),
+        };
+        self.transport.post("/v1/attachments", &upload)
+    }
+
+    /// Returns the transaction status, waiting up to `wait_ms` for it to be committed.
+    /// Nodes wait at most 2 s and may not wait when busy, poll again if still pending.
+    pub fn transaction_status(
+        &self,
+        tx_hash: &Hash,
+        wait_ms: u64,
+    ) -> Result<TransactionStatus, ClientError> {
+        self.transport.get(&format!(
+            "/v1/transactions/{}/status?wait_ms={}",
+            tx_hash, wait_ms
+        ))
+    }
+
+    pub fn sign_ballot(&self, proposals: &ProposalList) -> Result<Ballot, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        let proposals = proposals.clone().into_bytes();
+        Ok(Ballot::new(
+            public_key,
+            ::std::str::from_utf8(proposals.as_slice()).unwrap(),
+            secret_key,
+        ))
+    }
+
+    pub fn sign_vote(
+        &self,
+        proposals_hash: &Hash,
+        vote_req: &VoteRequest,
+    ) -> Result<Vote, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(Vote::new(
+            public_key,
+            proposals_hash,
+            vote_req.proposal_id,
+            &vote_req.proposal_subject,
+            secret_key,
+        ))
+    }
+
+    pub fn sign_answers(
+        &self,
+        proposals_hash: &Hash,
+        answers: &[Answer],
+    ) -> Result<VoteQuestions, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(VoteQuestions::new(
+            public_key,
+            proposals_hash,
+            &serde_json::to_string(answers)?,
+            secret_key,
+        ))
+    }
+
+    pub fn sign_finalize(&self, proposals_hash: &Hash) -> Result<FinalizeBallot, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(FinalizeBallot::new(public_key, proposals_hash, secret_key))
+    }
+
+    pub fn sign_sponsor(&self, proposals_hash: &Hash) -> Result<SponsorBallot, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(SponsorBallot::new(public_key, proposals_hash, secret_key))
+    }
+
+    pub fn sign_amend(
+        &self,
+        proposals_hash: &Hash,
+        amendment: &Amendment,
+    ) -> Result<AmendBallot, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(AmendBallot::new(
+            public_key,
+            proposals_hash,
+            &serde_json::to_string(amendment)?,
+            secret_key,
+        ))
+    }
+
+    pub fn sign_nominate(
+        &self,
+        proposals_hash: &Hash,
+        nomination: &NominationRequest,
+    ) -> Result<Nominate, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(Nominate::new(
+            public_key,
+            proposals_hash,
+            &nomination.subject,
+            &nomination.description,
+            secret_key,
+        ))
+    }
+
+    pub fn sign_propose_config(&self, config: &BallotConfig) -> Result<ProposeConfig, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(ProposeConfig::new(
+            public_key,
+            &serde_json::to_string(config)?,
+            secret_key,
+        ))
+    }
+
+    pub fn sign_vote_config(&self, config_hash: &Hash) -> Result<VoteConfig, ClientError> {
+        let (public_key, secret_key) = self.keys()?;
+        Ok(VoteConfig::new(public_key, config_hash, secret_key))
+    }
+
+    pub fn create_ballot(&self, proposals: &ProposalList) -> Result<BallotResponse, ClientError> {
+        let tx = self.sign_ballot(proposals)?;
+        let resp = self.submit(&SignedTransaction::Json(BallotTransactions::Ballot(tx)))?;
+
+        Ok(BallotResponse {
+            tx_hash: resp.tx_hash,
+            proposals_hash: proposals.hash(),
+        })
+    }
+
+    pub fn vote(
+        &self,
+        proposals_hash: &Hash,
+        vote_req: &VoteRequest,
+    ) -> Result<VoteResponse, ClientError> {
+        let tx = self.sign_vote(proposals_hash, vote_req)?;
+        let resp = self.submit(&SignedTransaction::Json(BallotTransactions::Vote(tx)))?;
+
+        Ok(VoteResponse {
+            tx_hash: resp.tx_hash,
+        })
+    }
+
+    /// Answers every question of a ballot with questions in one transaction.
+    pub fn answer(
+        &self,
+        proposals_hash: &Hash,
+        answers: &[Answer],
+    ) -> Result<VoteResponse, ClientError> {
+        let tx = self.sign_answers(proposals_hash, answers)?;
+        let resp = self.submit(&SignedTransaction::Json(BallotTransactions::VoteQuestions(tx)))?;
+
+        Ok(VoteResponse {
+            tx_hash: resp.tx_hash,
+        })
+    }
+
+    /// Records the result of a closed ballot and applies the winning proposal's action.
+    pub fn finalize(&self, proposals_hash: &Hash) -> Result<TransactionResponse, ClientError> {
+        let tx = self.sign_finalize(proposals_hash)?;
+        self.submit(&SignedTransaction::Json(BallotTransactions::FinalizeBallot(tx)))
+    }
+
+    /// Backs a ballot seeking sponsors, it opens with the last sponsor it needs.
+    pub fn sponsor(&self, proposals_hash: &Hash) -> Result<TransactionResponse, ClientError> {
+        let tx = self.sign_sponsor(proposals_hash)?;
+        self.submit(&SignedTransaction::Json(BallotTransactions::SponsorBallot(tx)))
+    }
+
+    /// Amends the proposals of a ballot in discussion, only its author may.
+    pub fn amend(
+        &self,
+        proposals_hash: &Hash,
+        amendment: &Amendment,
+    ) -> Result<TransactionResponse, ClientError> {
+        let tx = self.sign_amend(proposals_hash, amendment)?;
+        self.submit(&SignedTransaction::Json(BallotTransactions::AmendBallot(tx)))
+    }
+
+    /// Adds a write-in proposal to a ballot accepting nominations, its id is the next
+    /// free one when the transaction is executed.
+    pub fn nominate(
+        &self,
+        proposals_hash: &Hash,
+        nomination: &NominationRequest,
+    ) -> Result<TransactionResponse, ClientError> {
+        let tx = self.sign_nominate(proposals_hash, nomination)?;
+        self.submit(&SignedTransaction::Json(BallotTransactions::Nominate(tx)))
+    }
+
+    /// Proposes a configuration change, signing it also counts as its first approval.
+    pub fn propose_config(&self, config: &BallotConfig) -> Result<ConfigResponse, ClientError> {
+        let tx = self.sign_propose_config(config)?;
+        let resp = self.submit(&SignedTransaction::Json(BallotTransactions::ProposeConfig(tx)))?;
+
+        Ok(ConfigResponse {
+            tx_hash: resp.tx_hash,
+            config_hash: config.hash(),
+        })
+    }
+
+    pub fn vote_config(&self, config_hash: &Hash) -> Result<ConfigResponse, ClientError> {
+        let tx = self.sign_vote_config(config_hash)?;
+        let resp = self.submit(&SignedTransaction::Json(BallotTransactions::VoteConfig(tx)))?;
+
+        Ok(ConfigResponse {
+            tx_hash: resp.tx_hash,
+            config_hash: *config_hash,
+        })
+    }
+
+    pub fn submit(
+        &self,
+        signed_tx: &SignedTransaction,
+    ) -> Result<TransactionResponse, ClientError> {
+        self.transport.post("/v1/transactions", signed_tx)
+    }
+
+    fn keys(&self) -> Result<(&PublicKey, &SecretKey), ClientError> {
+        match self.keys {
+            Some((ref public_key, ref secret_key)) => Ok((public_key, secret_key)),
+            None => Err(ClientError::MissingKeys),
+        }
+    }
+}
@@ -1,10 +1,29 @@
 use exonum::blockchain::ExecutionError;
+use exonum::crypto::{Hash, PublicKey};
 
-use serde_json::Error as JsonError;
+use serde_json::{self, Error as JsonError};
 
-#[derive(Debug)]
-#[repr(u8)]
-pub enum ErrorCode {
+/// Defines `ErrorCode` with `from_u8` covering every code.
+macro_rules! define_error_codes {
+    ($($variant:ident = $code:tt,)+) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+        #[repr(u8)]
+        pub enum ErrorCode {
+            $($variant = $code,)+
+        }
+
+        impl ErrorCode {
+            pub fn from_u8(code: u8) -> Option<ErrorCode> {
+                match code {
+                    $($code => Some(ErrorCode::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+define_error_codes! {
     BallotNoneExists = 0,
     BallotAlreadyPosted = 1,
     InvalidProposals = 2,
@@ -13,59 +32,382 @@ pub enum ErrorCode {
     VotedProposalNoneExists = 5,
     AlreadyVoted = 6,
     BallotAlreadyClosed = 7,
+    NoProposals = 8,
+    TooManyProposals = 9,
+    SubjectTooLong = 10,
+    DescriptionTooLong = 11,
+    EmptySubject = 12,
+    DeadlineTooSoon = 13,
+    DeadlineTooFar = 14,
+    InvalidConfig = 15,
+    ConfigAlreadyProposed = 16,
+    ConfigProposalNoneExists = 17,
+    ConfigAlreadyApplied = 18,
+    InvalidAction = 19,
+    BallotNotClosed = 20,
+    BallotAlreadyFinalized = 21,
+    TooManyOpenBallots = 22,
+    BallotIntervalTooShort = 23,
+    SponsorsUnreachable = 24,
+    BallotSeekingSponsors = 25,
+    NotSeekingSponsors = 26,
+    AlreadySponsored = 27,
+    DiscussionOutOfRange = 28,
+    DiscussionClosed = 29,
+    BallotInDiscussion = 30,
+    NotBallotAuthor = 31,
+    InvalidAmendment = 32,
+    AmendedProposalNoneExists = 33,
+    NominationsOutOfRange = 34,
+    NominationsClosed = 35,
+    BallotNominating = 36,
+    InvalidQuestions = 37,
+    InvalidAnswers = 38,
+    InvalidMetadata = 39,
+    ParentBallotNoneExists = 40,
+    InvalidAttachment = 41,
+    ProposalsTooLong = 42,
+    TooManyRevisions = 43,
+    AmendmentTooLong = 44,
+    NominatedProposal = 45,
+    TooManyNominations = 46,
     InternalError = 255,
 }
 
-#[derive(Debug, Fail)]
-pub(crate) enum Error {
-    #[fail(display = "Ballot doesn't exist")]
-    BallotNoneExists,
+impl ErrorCode {
+    pub fn description(&self) -> &'static str {
+        use self::ErrorCode::*;
+
+        match *self {
+            BallotNoneExists => "Ballot doesn't exist",
+            BallotAlreadyPosted => "Ballot already Posted",
+            InvalidProposals => "Invalid proposals json",
+            PostDuplicateProposalId => "Posted proposals contain duplicate id",
+            UnknownSender => "Not authored by a voter",
+            VotedProposalNoneExists => "Voted proposal doesn't exist",
+            AlreadyVoted => "Already Voted",
+            BallotAlreadyClosed => "Ballot already closed",
+            NoProposals => "Posted proposals are empty",
+            TooManyProposals => "Too many proposals",
+            SubjectTooLong => "Proposal subject is too long",
+            DescriptionTooLong => "Proposal description is too long",
+            EmptySubject => "Proposal subject is empty",
+            DeadlineTooSoon => "Ballot deadline is too soon",
+            DeadlineTooFar => "Ballot deadline is too far",
+            InvalidConfig => "Invalid service config",
+            ConfigAlreadyProposed => "Config already proposed",
+            ConfigProposalNoneExists => "Config proposal doesn't exist",
+            ConfigAlreadyApplied => "Config already applied",
+            InvalidAction => "Invalid proposal action",
+            BallotNotClosed => "Ballot isn't closed yet",
+            BallotAlreadyFinalized => "Ballot already finalized",
+            TooManyOpenBallots => "Author has too many open ballots",
+            BallotIntervalTooShort => "Author posted a ballot too recently",
+            SponsorsUnreachable => "Ballot needs more sponsors than there are voters",
+            BallotSeekingSponsors => "Ballot is seeking sponsors",
+            NotSeekingSponsors => "Ballot isn't seeking sponsors",
+            AlreadySponsored => "Already sponsored",
+            DiscussionOutOfRange => "Discussion window doesn't end before the deadline",
+            DiscussionClosed => "Ballot isn't open for amendments",
+            BallotInDiscussion => "Ballot is in discussion",
+            NotBallotAuthor => "Not authored by the ballot author",
+            InvalidAmendment => "Invalid amendment json",
+            AmendedProposalNoneExists => "Amended proposal doesn't exist",
+            NominationsOutOfRange => "Nomination phase doesn't end before the deadline",
+            NominationsClosed => "Ballot isn't open for nominations",
+            BallotNominating => "Ballot is accepting nominations",
+            InvalidQuestions => "Invalid ballot questions",
+            InvalidAnswers => "Invalid answers",
+            InvalidMetadata => "Invalid ballot metadata",
+            ParentBallotNoneExists => "Parent ballot doesn't exist",
+            InvalidAttachment => "Invalid proposal attachment",
+            ProposalsTooLong => "Posted proposals json is too long",
+            TooManyRevisions => "Ballot has too many revisions",
+            AmendmentTooLong => "Amendment json is too long",
+            NominatedProposal => "Proposal was nominated by another voter",
+            TooManyNominations => "Voter has too many nominations on the ballot",
+            InternalError => "Internal Error",
+        }
+    }
+}
+
+/// Service error with the details of the failed check.
+///
+/// The `ExecutionError` description is the json below, `Error::from_description`
+/// decodes it for the api, which also shows the display text.
+///
+/// json example:
+/// {"error": "ballot_already_closed", "deadline": 30, "height": 31}
+#[derive(Debug, Clone, PartialEq, Fail, Serialize, Deserialize)]
+#[serde(tag = "error", rename_all = "snake_case")]
+pub enum Error {
+    #[fail(display = "Ballot {} doesn't exist", proposals_hash)]
+    BallotNoneExists { proposals_hash: Hash },
+
+    #[fail(display = "Ballot {} already Posted", proposals_hash)]
+    BallotAlreadyPosted { proposals_hash: Hash },
+
+    #[fail(display = "Invalid proposals json at line {} column {}: {}", line, column, message)]
+    InvalidProposals {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+
+    #[fail(display = "Posted proposals contain duplicate id {}", proposal_id)]
+    PostDuplicateProposalId { proposal_id: u64 },
+
+    #[fail(display = "Not authored by a voter: {}", sender)]
+    UnknownSender { sender: PublicKey },
+
+    #[fail(display = "Voted proposal {} {} doesn't exist", proposal_id, proposal_subject)]
+    VotedProposalNoneExists {
+        proposal_id: u64,
+        proposal_subject: String,
+    },
+
+    #[fail(display = "Already Voted by {}", voter)]
+    AlreadyVoted { voter: PublicKey },
+
+    #[fail(display = "Ballot already closed, deadline {} height {}", deadline, height)]
+    BallotAlreadyClosed { deadline: u64, height: u64 },
+
+    #[fail(display = "Posted proposals are empty")]
+    NoProposals,
+
+    #[fail(display = "Too many proposals: {}, max {}", count, max)]
+    TooManyProposals { count: u64, max: u32 },
+
+    #[fail(display = "Subject of proposal {} is too long: {} bytes, max {}", proposal_id, len, max)]
+    SubjectTooLong { proposal_id: u64, len: u64, max: u32 },
+
+    #[fail(display = "Description of proposal {} is too long: {} bytes, max {}", proposal_id, len,
+           max)]
+    DescriptionTooLong { proposal_id: u64, len: u64, max: u32 },
+
+    #[fail(display = "Subject of proposal {} is empty", proposal_id)]
+    EmptySubject { proposal_id: u64 },
+
+    #[fail(display = "Ballot deadline {} is too soon, height {} min deadline {}", deadline, height,
+           min_deadline)]
+    DeadlineTooSoon {
+        deadline: u64,
+        height: u64,
+        min_deadline: u64,
+    },
+
+    #[fail(display = "Ballot deadline {} is too far, height {} max deadline {}", deadline, height,
+           max_deadline)]
+    DeadlineTooFar {
+        deadline: u64,
+        height: u64,
+        max_deadline: u64,
+    },
+
+    #[fail(display = "Invalid service config: {}", message)]
+    InvalidConfig { message: String },
+
+    #[fail(display = "Config {} already proposed", config_hash)]
+    ConfigAlreadyProposed { config_hash: Hash },
+
+    #[fail(display = "Config proposal {} doesn't exist", config_hash)]
+    ConfigProposalNoneExists { config_hash: Hash },
 
-    #[fail(display = "Ballot already Posted")]
-    BallotAlreadyPosted,
+    #[fail(display = "Config {} already applied at height {}", config_hash, height)]
+    ConfigAlreadyApplied { config_hash: Hash, height: u64 },
 
-    #[fail(display = "Invalid proposals json: {}", _0)]
-    InvalidProposals(#[cause] JsonError),
+    #[fail(display = "Invalid action of proposal {}: {}", proposal_id, message)]
+    InvalidAction { proposal_id: u64, message: String },
 
-    #[fail(display = "Posted proposals contain duplicate id")]
-    PostDuplicateProposalId,
+    #[fail(display = "Ballot with deadline {} isn't closed at height {}", deadline, height)]
+    BallotNotClosed { deadline: u64, height: u64 },
 
-    #[fail(display = "Not authored by a validator")]
-    UnknownSender,
+    #[fail(display = "Ballot {} already finalized at height {}", proposals_hash, height)]
+    BallotAlreadyFinalized { proposals_hash: Hash, height: u64 },
 
-    #[fail(display = "Voted proposal doesn't exist")]
-    VotedProposalNoneExists,
+    #[fail(display = "Author has {} open ballots, max {}", open, max)]
+    TooManyOpenBallots { open: u64, max: u32 },
 
-    #[fail(display = "Already Voted")]
-    AlreadyVoted,
+    #[fail(display = "Ballot at height {} follows the author's ballot at {}, min interval {}",
+           height, last_height, min_interval)]
+    BallotIntervalTooShort {
+        height: u64,
+        last_height: u64,
+        min_interval: u64,
+    },
 
-    #[fail(display = "Ballot already closed")]
-    BallotAlreadyClosed,
+    #[fail(display = "Ballot needs {} sponsors, only {} voters besides the author", min_sponsors,
+           eligible)]
+    SponsorsUnreachable { min_sponsors: u32, eligible: u64 },
 
-    #[fail(display = "Internal Error {}", _0)]
-    InternalError(String),
+    #[fail(display = "Ballot {} is seeking sponsors, {} of {}", proposals_hash, sponsors,
+           min_sponsors)]
+    BallotSeekingSponsors {
+        proposals_hash: Hash,
+        sponsors: u64,
+        min_sponsors: u32,
+    },
+
+    #[fail(display = "Ballot {} isn't seeking sponsors", proposals_hash)]
+    NotSeekingSponsors { proposals_hash: Hash },
+
+    #[fail(display = "Already sponsored by {}", sponsor)]
+    AlreadySponsored { sponsor: PublicKey },
+
+    #[fail(display = "Discussion until {} has to end after height {} and before deadline {}",
+           discussion_until, height, deadline)]
+    DiscussionOutOfRange {
+        discussion_until: u64,
+        height: u64,
+        deadline: u64,
+    },
+
+    #[fail(display = "Ballot {} isn't open for amendments at height {}", proposals_hash, height)]
+    DiscussionClosed { proposals_hash: Hash, height: u64 },
+
+    #[fail(display = "Ballot is in discussion until {}, height {}", discussion_until, height)]
+    BallotInDiscussion { discussion_until: u64, height: u64 },
+
+    #[fail(display = "Not authored by the ballot author: {}", sender)]
+    NotBallotAuthor { sender: PublicKey },
+
+    #[fail(display = "Invalid amendment json: {}", message)]
+    InvalidAmendment { message: String },
+
+    #[fail(display = "Amended proposal {} doesn't exist", proposal_id)]
+    AmendedProposalNoneExists { proposal_id: u64 },
+
+    #[fail(display = "Nominations until {} have to end after height {} and before deadline {}",
+           nominations_until, height, deadline)]
+    NominationsOutOfRange {
+        nominations_until: u64,
+        height: u64,
+        deadline: u64,
+    },
+
+    #[fail(display = "Ballot {} isn't open for nominations at height {}", proposals_hash, height)]
+    NominationsClosed { proposals_hash: Hash, height: u64 },
+
+    #[fail(display = "Ballot accepts nominations until {}, height {}", nominations_until, height)]
+    BallotNominating { nominations_until: u64, height: u64 },
+
+    #[fail(display = "Invalid ballot questions: {}", message)]
+    InvalidQuestions { message: String },
+
+    #[fail(display = "Invalid answers: {}", message)]
+    InvalidAnswers { message: String },
+
+    #[fail(display = "Invalid ballot metadata: {}", message)]
+    InvalidMetadata { message: String },
+
+    #[fail(display = "Parent ballot {} doesn't exist", parent)]
+    ParentBallotNoneExists { parent: Hash },
+
+    #[fail(display = "Invalid attachment of proposal {}: {}", proposal_id, message)]
+    InvalidAttachment { proposal_id: u64, message: String },
+
+    #[fail(display = "Proposals json is too long: {} bytes, max {}", len, max)]
+    ProposalsTooLong { len: u64, max: u64 },
+
+    #[fail(display = "Ballot {} has {} revisions, max {}", proposals_hash, revisions, max)]
+    TooManyRevisions {
+        proposals_hash: Hash,
+        revisions: u64,
+        max: u32,
+    },
+
+    #[fail(display = "Amendment json is too long: {} bytes, max {}", len, max)]
+    AmendmentTooLong { len: u64, max: u64 },
+
+    #[fail(display = "Proposal {} was nominated by {} and can't be amended", proposal_id,
+           nominator)]
+    NominatedProposal {
+        proposal_id: u64,
+        nominator: PublicKey,
+    },
+
+    #[fail(display = "Voter has {} nominations on the ballot, max {}", nominations, max)]
+    TooManyNominations { nominations: u64, max: u32 },
+
+    #[fail(display = "Internal Error: vote position {} isn't reserved for {}", validator_id,
+           sender)]
+    VotePositionNotReserved { validator_id: u64, sender: PublicKey },
 }
 
 impl Error {
-    fn code(&self) -> ErrorCode {
+    /// Decodes the description of a failed transaction, `None` if it wasn't written
+    /// by this service.
+    pub fn from_description(description: &str) -> Option<Error> {
+        serde_json::from_str(description).ok()
+    }
+
+    pub(crate) fn invalid_proposals(e: JsonError) -> Error {
+        Error::InvalidProposals {
+            message: e.to_string(),
+            line: e.line(),
+            column: e.column(),
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
         use self::Error::*;
 
         match *self {
-            BallotNoneExists => ErrorCode::BallotNoneExists,
-            BallotAlreadyPosted => ErrorCode::BallotAlreadyPosted,
-            InvalidProposals(_) => ErrorCode::InvalidProposals,
-            PostDuplicateProposalId => ErrorCode::PostDuplicateProposalId,
-            UnknownSender => ErrorCode::UnknownSender,
-            VotedProposalNoneExists => ErrorCode::VotedProposalNoneExists,
-            AlreadyVoted => ErrorCode::AlreadyVoted,
-            BallotAlreadyClosed => ErrorCode::BallotAlreadyClosed,
-            InternalError(_) => ErrorCode::InternalError,
+            BallotNoneExists { .. } => ErrorCode::BallotNoneExists,
+            BallotAlreadyPosted { .. } => ErrorCode::BallotAlreadyPosted,
+            InvalidProposals { .. } => ErrorCode::InvalidProposals,
+            PostDuplicateProposalId { .. } => ErrorCode::PostDuplicateProposalId,
+            UnknownSender { .. } => ErrorCode::UnknownSender,
+            VotedProposalNoneExists { .. } => ErrorCode::VotedProposalNoneExists,
+            AlreadyVoted { .. } => ErrorCode::AlreadyVoted,
+            BallotAlreadyClosed { .. } => ErrorCode::BallotAlreadyClosed,
+            NoProposals => ErrorCode::NoProposals,
+            TooManyProposals { .. } => ErrorCode::TooManyProposals,
+            SubjectTooLong { .. } => ErrorCode::SubjectTooLong,
+            DescriptionTooLong { .. } => ErrorCode::DescriptionTooLong,
+            EmptySubject { .. } => ErrorCode::EmptySubject,
+            DeadlineTooSoon { .. } => ErrorCode::DeadlineTooSoon,
+            DeadlineTooFar { .. } => ErrorCode::DeadlineTooFar,
+            InvalidConfig { .. } => ErrorCode::InvalidConfig,
+            ConfigAlreadyProposed { .. } => ErrorCode::ConfigAlreadyProposed,
+            ConfigProposalNoneExists { .. } => ErrorCode::ConfigProposalNoneExists,
+            ConfigAlreadyApplied { .. } => ErrorCode::ConfigAlreadyApplied,
+            InvalidAction { .. } => ErrorCode::InvalidAction,
+            BallotNotClosed { .. } => ErrorCode::BallotNotClosed,
+            BallotAlreadyFinalized { .. } => ErrorCode::BallotAlreadyFinalized,
+            TooManyOpenBallots { .. } => ErrorCode::TooManyOpenBallots,
+            BallotIntervalTooShort { .. } => ErrorCode::BallotIntervalTooShort,
+            SponsorsUnreachable { .. } => ErrorCode::SponsorsUnreachable,
+            BallotSeekingSponsors { .. } => ErrorCode::BallotSeekingSponsors,
+            NotSeekingSponsors { .. } => ErrorCode::NotSeekingSponsors,
+            AlreadySponsored { .. } => ErrorCode::AlreadySponsored,
+            DiscussionOutOfRange { .. } => ErrorCode::DiscussionOutOfRange,
+            DiscussionClosed { .. } => ErrorCode::DiscussionClosed,
+            BallotInDiscussion { .. } => ErrorCode::BallotInDiscussion,
+            NotBallotAuthor { .. } => ErrorCode::NotBallotAuthor,
+            InvalidAmendment { .. } => ErrorCode::InvalidAmendment,
+            AmendedProposalNoneExists { .. } => ErrorCode::AmendedProposalNoneExists,
+            NominationsOutOfRange { .. } => ErrorCode::NominationsOutOfRange,
+            NominationsClosed { .. } => ErrorCode::NominationsClosed,
+            BallotNominating { .. } => ErrorCode::BallotNominating,
+            InvalidQuestions { .. } => ErrorCode::InvalidQuestions,
+            InvalidAnswers { .. } => ErrorCode::InvalidAnswers,
+            InvalidMetadata { .. } => ErrorCode::InvalidMetadata,
+            ParentBallotNoneExists { .. } => ErrorCode::ParentBallotNoneExists,
+            InvalidAttachment { .. } => ErrorCode::InvalidAttachment,
+            ProposalsTooLong { .. } => ErrorCode::ProposalsTooLong,
+            TooManyRevisions { .. } => ErrorCode::TooManyRevisions,
+            AmendmentTooLong { .. } => ErrorCode::AmendmentTooLong,
+            NominatedProposal { .. } => ErrorCode::NominatedProposal,
+            TooManyNominations { .. } => ErrorCode::TooManyNominations,
+            VotePositionNotReserved { .. } => ErrorCode::InternalError,
         }
     }
 }
 
 impl From<Error> for ExecutionError {
     fn from(value: Error) -> ExecutionError {
-        ExecutionError::with_description(value.code() as u8, value.to_string())
+        let description = serde_json::to_string(&value).unwrap();
+        ExecutionError::with_description(value.code() as u8, description)
     }
 }
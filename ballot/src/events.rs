@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::time::Duration;
+
+use exonum::blockchain::{Schema as CoreSchema, ServiceContext, TransactionErrorType,
+                         TransactionSet};
+use exonum::crypto::{CryptoHash, Hash, PublicKey};
+use exonum::helpers::Height;
+use exonum::storage::Snapshot;
+use iron::response::WriteBody;
+use serde_json;
+
+use SERVICE_ID;
+use error::{Error as ServiceError, ErrorCode};
+use schema::{Outcome, ProposalAction, ProposalList, Revision, Schema};
+use transactions::Transactions as BallotTransactions;
+
+/// Interval between keep-alive comments sent to idle event stream clients.
+const KEEP_ALIVE_INTERVAL: u64 = 15;
+
+/// Most event streams served at once. Each stream holds an iron worker thread, the cap
+/// leaves the rest of the pool to the other endpoints.
+pub const MAX_EVENT_STREAMS: usize = 4;
+
+/// Events buffered for each subscriber, one that falls further behind is dropped.
+pub(crate) const SUBSCRIBER_BUFFER: usize = 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum BallotEvent {
+    BallotCreated {
+        proposals_hash: Hash,
+        tx_hash: Hash,
+        from: PublicKey,
+        height: u64,
+    },
+    VoteCast {
+        proposals_hash: Hash,
+        tx_hash: Hash,
+        from: PublicKey,
+        proposal_id: u64,
+        height: u64,
+    },
+    /// Answers to the questions of a ballot, see `VoteQuestions`.
+    QuestionsAnswered {
+        proposals_hash: Hash,
+        tx_hash: Hash,
+        from: PublicKey,
+        height: u64,
+    },
+    BallotClosed {
+        proposals_hash: Hash,
+        deadline: u64,
+    },
+    /// `revision` counts the amendments of the ballot so far, `revision_hash` is the
+    /// hash of its proposals after this one.
+    BallotAmended {
+        proposals_hash: Hash,
+        tx_hash: Hash,
+        revision: u64,
+        revision_hash: Hash,
+        height: u64,
+    },
+    /// `proposal_id` is the id assigned to the nominated proposal.
+    ProposalNominated {
+        proposals_hash: Hash,
+        tx_hash: Hash,
+        from: PublicKey,
+        proposal_id: u64,
+        revision: u64,
+        height: u64,
+    },
+    /// `opened` is set by the sponsor that brought the ballot to `min_sponsors`.
+    BallotSponsored {
+        proposals_hash: Hash,
+        tx_hash: Hash,
+        from: PublicKey,
+        opened: bool,
+        height: u64,
+    },
+    BallotFinalized {
+        proposals_hash: Hash,
+        tx_hash: Hash,
+        outcome: Outcome,
+        action: Option<ProposalAction>,
+        height: u64,
+    },
+    TxRejected {
+        proposals_hash: Option<Hash>,
+        tx_hash: Hash,
+        error_code: Option<ErrorCode>,
+        description: Option<String>,
+        details: Option<ServiceError>,
+        height: u64,
+    },
+}
+
+impl BallotEvent {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            BallotEvent::BallotCreated { .. } => "BallotCreated",
+            BallotEvent::VoteCast { .. } => "VoteCast",
+            BallotEvent::QuestionsAnswered { .. } => "QuestionsAnswered",
+            BallotEvent::BallotClosed { .. } => "BallotClosed",
+            BallotEvent::BallotAmended { .. } => "BallotAmended",
+            BallotEvent::ProposalNominated { .. } => "ProposalNominated",
+            BallotEvent::BallotSponsored { .. } => "BallotSponsored",
+            BallotEvent::BallotFinalized { .. } => "BallotFinalized",
+            BallotEvent::TxRejected { .. } => "TxRejected",
+        }
+    }
+
+    pub fn proposals_hash(&self) -> Option<&Hash> {
+        match *self {
+            BallotEvent::BallotCreated {
+                ref proposals_hash, ..
+            }
+            | BallotEvent::VoteCast {
+                ref proposals_hash, ..
+            }
+            | BallotEvent::QuestionsAnswered {
+                ref proposals_hash, ..
+            }
+            | BallotEvent::BallotClosed {
+                ref proposals_hash, ..
+            }
+            | BallotEvent::BallotAmended {
+                ref proposals_hash, ..
+            }
+            | BallotEvent::ProposalNominated {
+                ref proposals_hash, ..
+            }
+            | BallotEvent::BallotSponsored {
+                ref proposals_hash, ..
+            }
+            | BallotEvent::BallotFinalized {
+                ref proposals_hash, ..
+            } => Some(proposals_hash),
+            BallotEvent::TxRejected {
+                ref proposals_hash, ..
+            } => proposals_hash.as_ref(),
+        }
+    }
+
+    /// Collects events of the block committed at `height`.
+    pub fn from_block(snapshot: &Snapshot, height: Height) -> Vec<BallotEvent> {
+        let core_schema = CoreSchema::new(snapshot);
+        let transactions = core_schema.transactions();
+        let results = core_schema.transaction_results();
+
+        let mut events = Vec::new();
+        let mut revised = HashMap::new();
+        for tx_hash in core_schema.block_transactions(height).iter() {
+            let raw = match transactions.get(&tx_hash) {
+                Some(raw) => raw,
+                None => continue,
+            };
+            if raw.service_id() != SERVICE_ID {
+                continue;
+            }
+            let tx = match BallotTransactions::tx_from_raw(raw) {
+                Ok(tx) => tx,
+                Err(_) => continue,
+            };
+            let result = match results.get(&tx_hash) {
+                Some(result) => result,
+                None => continue,
+            };
+
+            let proposals_hash = match tx {
+                BallotTransactions::Ballot(ref ballot) => {
+                    ProposalList::try_deserialize(ballot.proposals().as_bytes())
+                        .ok()
+                        .map(|proposals| proposals.hash())
+                }
+                BallotTransactions::Vote(ref vote) => Some(*vote.proposals_hash()),
+                BallotTransactions::FinalizeBallot(ref finalize) => {
+                    Some(*finalize.proposals_hash())
+                }
+                BallotTransactions::SponsorBallot(ref sponsor) => Some(*sponsor.proposals_hash()),
+                BallotTransactions::AmendBallot(ref amend) => Some(*amend.proposals_hash()),
+                BallotTransactions::Nominate(ref nominate) => Some(*nominate.proposals_hash()),
+                BallotTransactions::VoteQuestions(ref vote) => Some(*vote.proposals_hash()),
+                BallotTransactions::ProposeConfig(_) | BallotTransactions::VoteConfig(_) => None,
+            };
+
+            let event = match (result, tx) {
+                (Ok(()), BallotTransactions::Ballot(ballot)) => BallotEvent::BallotCreated {
+                    proposals_hash: proposals_hash.unwrap(),
+                    tx_hash,
+                    from: *ballot.from(),
+                    height: height.0,
+                },
+                (Ok(()), BallotTransactions::Vote(vote)) => BallotEvent::VoteCast {
+                    proposals_hash: *vote.proposals_hash(),
+                    tx_hash,
+                    from: *vote.from(),
+                    proposal_id: vote.proposal_id(),
+                    height: height.0,
+                },
+                (Ok(()), BallotTransactions::FinalizeBallot(finalize)) => {
+                    let finalization = Schema::new(snapshot)
+                        .finalizations()
+                        .get(finalize.proposals_hash());
+                    let finalization = match finalization {
+                        Some(finalization) => finalization,
+                        None => continue,
+                    };
+                    BallotEvent::BallotFinalized {
+                        proposals_hash: *finalize.proposals_hash(),
+                        tx_hash,
+                        outcome: finalization.outcome,
+                        action: finalization.action,
+                        height: height.0,
+                    }
+                }
+                (Ok(()), BallotTransactions::SponsorBallot(sponsor)) => {
+                    let opened_at = Schema::new(snapshot)
+                        .sponsorships()
+                        .get(sponsor.proposals_hash())
+                        .and_then(|sponsorship| sponsorship.opened_at);
+                    BallotEvent::BallotSponsored {
+                        proposals_hash: *sponsor.proposals_hash(),
+                        tx_hash,
+                        from: *sponsor.from(),
+                        opened: opened_at == Some(height.0),
+                        height: height.0,
+                    }
+                }
+                (Ok(()), BallotTransactions::AmendBallot(amend)) => {
+                    let (revision, stored) = match next_revision(
+                        snapshot,
+                        &mut revised,
+                        amend.proposals_hash(),
+                        height,
+                    ) {
+                        Some(next) => next,
+                        None => continue,
+                    };
+                    BallotEvent::BallotAmended {
+                        proposals_hash: *amend.proposals_hash(),
+                        tx_hash,
+                        revision,
+                        revision_hash: stored.hash,
+                        height: height.0,
+                    }
+                }
+                (Ok(()), BallotTransactions::Nominate(nominate)) => {
+                    let (revision, stored) = match next_revision(
+                        snapshot,
+                        &mut revised,
+                        nominate.proposals_hash(),
+                        height,
+                    ) {
+                        Some(next) => next,
+                        None => continue,
+                    };
+                    BallotEvent::ProposalNominated {
+                        proposals_hash: *nominate.proposals_hash(),
+                        tx_hash,
+                        from: *nominate.from(),
+                        proposal_id: stored.amendment.proposal_id(),
+                        revision,
+                        height: height.0,
+                    }
+                }
+                (Ok(()), BallotTransactions::VoteQuestions(vote)) => {
+                    BallotEvent::QuestionsAnswered {
+                        proposals_hash: *vote.proposals_hash(),
+                        tx_hash,
+                        from: *vote.from(),
+                        height: height.0,
+                    }
+                }
+                // Configuration changes aren't tied to a ballot.
+                (Ok(()), _) => continue,
+                (Err(e), _) => {
+                    let details = e.description().and_then(ServiceError::from_description);
+                    let description = match details {
+                        Some(ref details) => Some(details.to_string()),
+                        None => e.description().map(String::from),
+                    };
+                    BallotEvent::TxRejected {
+                        proposals_hash,
+                        tx_hash,
+                        error_code: match e.error_type() {
+                            TransactionErrorType::Code(code) => ErrorCode::from_u8(code),
+                            TransactionErrorType::Panic => None,
+                        },
+                        description,
+                        details,
+                        height: height.0,
+                    }
+                }
+            };
+            events.push(event);
+        }
+
+        // Votes are rejected once the latest height passes the deadline,
+        // so a ballot closes with the block at `deadline + 1`.
+        if let Some(deadline) = height.0.checked_sub(1) {
+            let schema = Schema::new(snapshot);
+            let proposals_hashes = schema.proposals_hash_by_ordinal();
+            for ordinal in schema.ordinals_by_deadline(deadline).iter() {
+                if let Some(proposals_hash) = proposals_hashes.get(ordinal) {
+                    events.push(BallotEvent::BallotClosed {
+                        proposals_hash,
+                        deadline,
+                    });
+                }
+            }
+        }
+
+        events
+    }
+}
+
+/// Finds the revision stored by the next successful amendment or nomination of a ballot
+/// in the block at `height`, with its number counting from 1. Events are collected
+/// after the block is committed, `revised` counts the revisions of each ballot already
+/// reported for the block.
+fn next_revision(
+    snapshot: &Snapshot,
+    revised: &mut HashMap<Hash, usize>,
+    proposals_hash: &Hash,
+    height: Height,
+) -> Option<(u64, Revision)> {
+    let schema = Schema::new(snapshot);
+    let reported = revised.entry(*proposals_hash).or_insert(0);
+    let next = schema
+        .revisions(proposals_hash)
+        .iter()
+        .enumerate()
+        .filter(|&(_, revision)| revision.height == height.0)
+        .nth(*reported)
+        .map(|(idx, revision)| (idx as u64 + 1, revision));
+    *reported += 1;
+    next
+}
+
+/// Fans block events out to the connected event stream clients.
+#[derive(Debug, Clone, Default)]
+pub struct EventHub {
+    subscribers: Arc<Mutex<Vec<SyncSender<BallotEvent>>>>,
+    streams: Arc<AtomicUsize>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        EventHub::default()
+    }
+
+    /// Receives the events of blocks committed from now on. A receiver that leaves
+    /// `SUBSCRIBER_BUFFER` events unread is disconnected.
+    pub fn subscribe(&self) -> Receiver<BallotEvent> {
+        let (sender, receiver) = mpsc::sync_channel(SUBSCRIBER_BUFFER);
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Stream of the events of `proposals_hash`, or of all ballots, for an api client.
+    /// `None` while `MAX_EVENT_STREAMS` streams are open.
+    pub(crate) fn open_stream(&self, proposals_hash: Option<Hash>) -> Option<EventStream> {
+        if self.streams.fetch_add(1, Ordering::SeqCst) >= MAX_EVENT_STREAMS {
+            self.streams.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(EventStream {
+            receiver: self.subscribe(),
+            proposals_hash,
+            streams: Arc::clone(&self.streams),
+        })
+    }
+
+    pub fn publish(&self, events: Vec<BallotEvent>) {
+        if events.is_empty() {
+            return;
+        }
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        // Disconnected and lagging subscribers are dropped alike.
+        subscribers.retain(|subscriber| {
+            events
+                .iter()
+                .all(|event| subscriber.try_send(event.clone()).is_ok())
+        });
+    }
+
+    pub(crate) fn handle_commit(&self, context: &ServiceContext) {
+        if self.subscribers.lock().unwrap().is_empty() {
+            return;
+        }
+        self.publish(BallotEvent::from_block(context.snapshot(), context.height()));
+    }
+}
+
+/// Server-Sent Events body, holds an iron worker thread until the client disconnects
+/// or falls behind, see `EventHub::open_stream`.
+pub(crate) struct EventStream {
+    receiver: Receiver<BallotEvent>,
+    proposals_hash: Option<Hash>,
+    streams: Arc<AtomicUsize>,
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.streams.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl WriteBody for EventStream {
+    fn write_body(&mut self, res: &mut Write) -> io::Result<()> {
+        let keep_alive = Duration::from_secs(KEEP_ALIVE_INTERVAL);
+        loop {
+            match self.receiver.recv_timeout(keep_alive) {
+                Ok(event) => {
+                    if let Some(ref proposals_hash) = self.proposals_hash {
+                        if event.proposals_hash() != Some(proposals_hash) {
+                            continue;
+                        }
+                    }
+                    let data = serde_json::to_string(&event).unwrap();
+                    write!(res, "event: {}\ndata: {}\n\n", event.name(), data)?;
+                }
+                Err(RecvTimeoutError::Timeout) => write!(res, ": keep-alive\n\n")?,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+            res.flush()?;
+        }
+    }
+}
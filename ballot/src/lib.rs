@@ -1,8 +1,11 @@
 extern crate bodyparser;
 #[macro_use]
 extern crate exonum;
+#[cfg(feature = "validator-set")]
+extern crate exonum_configuration;
 #[macro_use]
 extern crate failure;
+extern crate hyper;
 extern crate iron;
 #[macro_use]
 extern crate lazy_static;
@@ -12,36 +15,94 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "testkit"))]
 #[macro_use]
 extern crate exonum_testkit;
+#[cfg(any(test, feature = "testkit"))]
+extern crate iron_test;
 
 mod schema;
 mod transactions;
 mod error;
 mod api;
+pub mod client;
+mod events;
+mod migrations;
+#[cfg(feature = "validator-set")]
+mod validator_set;
+mod blobs;
 #[cfg(test)]
 mod tests;
 
-pub use error::ErrorCode;
-pub use schema::{BallotData, ProposalList, Schema};
-pub use transactions::{Ballot, Transactions as BallotTransactions, Vote};
+pub use api::{AnswerRecord, AttachmentStatus, AttachmentUpload, BallotHashInfo, BallotOrder,
+              BallotPage, BallotQuery, BallotResponse, BallotResults, ConfigResponse,
+              ParticipationStats, ProposalResult, QuestionResult, SignedTransaction, TallyProof,
+              NominationRequest, TransactionResponse, TransactionStatus, VoteRequest,
+              VoteResponse, VoterHistory, VotesInfo};
+pub use blobs::BlobStore;
+pub use error::{Error as BallotError, ErrorCode};
+pub use events::{BallotEvent, EventHub};
+pub use migrations::{migrate, MigrationError, SCHEMA_VERSION};
+pub use schema::{Amendment, Answer, AnswerSheet, Attachment, BallotConfig, BallotData,
+                 BallotLimits, BallotStatus, ConfigProposal, Electorate, Finalization,
+                 OpenBallots, Outcome, Proposal, ProposalAction, ProposalCount, ProposalList,
+                 Question, QuestionOutcome, Reference, Revision, RevisionHistory, Schema,
+                 Sponsorship, Tally, Threshold, VoteRecord, VotingMode};
+pub use transactions::{AmendBallot, Ballot, FinalizeBallot, Nominate, ProposeConfig,
+                       SponsorBallot, Transactions as BallotTransactions, Vote, VoteConfig,
+                       VoteQuestions};
 
 use exonum::encoding;
 use exonum::api::Api;
-use exonum::blockchain::{ApiContext, Service, Transaction, TransactionSet};
+use exonum::blockchain::{ApiContext, Service, ServiceContext, Transaction, TransactionSet};
 use exonum::crypto::Hash;
 use exonum::messages::RawTransaction;
-use exonum::storage::Snapshot;
+use exonum::storage::{Fork, Snapshot};
+use serde_json::Value;
 use iron::Handler;
 use router::Router;
 
 use api as BallotApi;
 
+#[cfg(not(feature = "validator-set"))]
 pub const SERVICE_ID: u16 = 1;
+/// The configuration service, which `ChangeValidators` ballots hand validator changes
+/// to, runs on id 1.
+#[cfg(feature = "validator-set")]
+pub const SERVICE_ID: u16 = 2;
 pub const SERVICE_NAME: &'static str = "ballot";
 
-pub struct BallotService;
+#[derive(Debug, Default)]
+pub struct BallotService {
+    events: EventHub,
+    config: BallotConfig,
+    blobs: BlobStore,
+}
+
+impl BallotService {
+    pub fn new() -> Self {
+        BallotService::default()
+    }
+
+    /// Service with the given genesis configuration, it applies to every ballot of the
+    /// network until changed by `ProposeConfig`.
+    pub fn with_config(config: BallotConfig) -> Self {
+        BallotService {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Keeps attachment contents in `blobs` instead of memory.
+    pub fn with_blob_store(self, blobs: BlobStore) -> Self {
+        BallotService { blobs, ..self }
+    }
+
+    /// Receives events of every block committed after this call.
+    pub fn subscribe(&self) -> ::std::sync::mpsc::Receiver<BallotEvent> {
+        self.events.subscribe()
+    }
+}
 
 impl Service for BallotService {
     fn service_name(&self) -> &'static str {
@@ -57,14 +118,30 @@ impl Service for BallotService {
         Ok(tx.into())
     }
 
+    /// New databases start at the current layout, older ones are upgraded by `migrate`.
+    /// The configuration is stored here since transactions only see the database,
+    /// and is returned to become part of the genesis service config.
+    fn initialize(&self, fork: &mut Fork) -> Value {
+        let mut schema = Schema::new(fork);
+        schema.schema_version_mut().set(SCHEMA_VERSION);
+        schema.config_mut().set(self.config.clone());
+        serde_json::to_value(&self.config).unwrap()
+    }
+
     fn state_hash(&self, snapshot: &Snapshot) -> Vec<Hash> {
         let schema = Schema::new(snapshot);
         schema.state_hash()
     }
 
+    fn handle_commit(&self, context: &ServiceContext) {
+        self.events.handle_commit(context);
+        #[cfg(feature = "validator-set")]
+        validator_set::handle_commit(context);
+    }
+
     fn public_api_handler(&self, ctx: &ApiContext) -> Option<Box<Handler>> {
         let mut router = Router::new();
-        let api = api::PublicApi::new(ctx);
+        let api = api::PublicApi::new(ctx, self.events.clone(), self.blobs.clone());
         api.wire(&mut router);
         Some(Box::new(router))
     }
@@ -0,0 +1,98 @@
+use exonum::blockchain::{Blockchain, Schema as CoreSchema};
+use exonum::crypto::CryptoHash;
+use exonum::storage::{Error as StorageError, Fork};
+
+use schema::{Schema, VoteRecord};
+use transactions::index_ballot;
+
+/// Version of the storage layout written by this code.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Migrations by the version they upgrade to, in ascending order. Databases without
+/// a `ballot.schema_version` entry predate versioning and are at version 0.
+///
+/// Migrations run outside of blocks, so they may only write indexes that aren't part
+/// of `state_hash`. Committed data in an older layout is read as it is and rewritten
+/// by the transactions that change it, see `Schema::electorate`, `Vote::save` and
+/// `FinalizeBallot`.
+const MIGRATIONS: &[(u32, fn(&mut Fork))] = &[(1, index_ballots)];
+
+#[derive(Debug, Fail)]
+pub enum MigrationError {
+    #[fail(display = "Storage error: {}", _0)]
+    Storage(#[cause] StorageError),
+
+    #[fail(display = "Database schema version {} is newer than supported {}", _0,
+           SCHEMA_VERSION)]
+    UnsupportedVersion(u32),
+}
+
+impl From<StorageError> for MigrationError {
+    fn from(e: StorageError) -> MigrationError {
+        MigrationError::Storage(e)
+    }
+}
+
+/// Upgrades the database of `blockchain` to `SCHEMA_VERSION`, called before the node
+/// starts. Migrations run in one fork, so the database is either fully upgraded or
+/// left untouched. They leave `state_hash` unchanged, so validators may upgrade one
+/// at a time.
+///
+/// Returns the version the database was at.
+pub fn migrate(blockchain: &mut Blockchain) -> Result<u32, MigrationError> {
+    let mut fork = blockchain.fork();
+    let version = Schema::new(&fork).schema_version().get().unwrap_or(0);
+    if version > SCHEMA_VERSION {
+        Err(MigrationError::UnsupportedVersion(version))?
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(version);
+    }
+
+    for &(target, migration) in MIGRATIONS.iter().filter(|&&(target, _)| target > version) {
+        migration(&mut fork);
+        Schema::new(&mut fork).schema_version_mut().set(target);
+    }
+    blockchain.merge(fork.into_patch())?;
+    Ok(version)
+}
+
+/// Version 1: builds the secondary indexes of ballots and votes committed before
+/// they existed, by deadline, author, category, tag, attachment and voter.
+pub(crate) fn index_ballots(view: &mut Fork) {
+    let mut votes = Vec::new();
+    {
+        let schema = Schema::new(&*view);
+        let locations = CoreSchema::new(&*view).transactions_locations();
+        for proposals_hash in schema.proposals_hash_by_ordinal().iter() {
+            for vote in schema.votes(&proposals_hash).into_iter().filter_map(|vote| vote) {
+                if let Some(location) = locations.get(&vote.hash()) {
+                    votes.push((location, vote));
+                }
+            }
+        }
+    }
+    // Votes by voter are kept in commit order.
+    votes.sort_by_key(|&(ref location, _)| {
+        (location.block_height(), location.position_in_block())
+    });
+
+    let ordinals = Schema::new(&*view).proposals_hash_by_ordinal().len();
+    let mut schema = Schema::new(view);
+    for ordinal in 0..ordinals {
+        let proposals_hash = schema.proposals_hash_by_ordinal().get(ordinal).unwrap();
+        let author = match schema.ballot(&proposals_hash) {
+            Some(ballot) => *ballot.from(),
+            None => continue,
+        };
+        if let Some(proposals) = schema.proposals(&proposals_hash) {
+            index_ballot(&mut schema, ordinal, &author, &proposals);
+        }
+    }
+    for (location, vote) in votes {
+        let voter = *vote.from();
+        schema
+            .votes_by_voter_mut(&voter)
+            .push(VoteRecord::new(vote, location.block_height().0));
+    }
+}
@@ -1,8 +1,12 @@
 use std::borrow::Cow;
 use std::ops::Deref;
 
+use exonum::blockchain::{Schema as CoreSchema, StoredConfiguration, ValidatorKeys};
 use exonum::crypto::{self, CryptoHash, Hash, PublicKey, Signature};
-use exonum::storage::{Fork, ProofListIndex, ProofMapIndex, Snapshot, StorageValue};
+use exonum::helpers::Height;
+use exonum::messages::Message;
+use exonum::storage::{Entry, Fork, KeySetIndex, ListIndex, MapIndex, ProofListIndex, ProofMapIndex,
+                      Snapshot, StorageValue};
 use serde_json::{self, Error as JsonError};
 
 use transactions::{Ballot, Vote};
@@ -13,12 +17,45 @@ macro_rules! define_names {
     )
 }
 
+// Every index of the service is committed to `state_hash` in one of three ways, see
+// `Schema::state_hash` for the full list:
+//
+// - top level proof indexes contribute their own root hash, entries their value hash;
+// - proof index families keyed by proposals hash are committed through a hash field
+//   of the ballot's `BallotData`, which lives in `BALLOTS`;
+// - non-proof indexes may only hold data derived from committed indexes, or node local
+//   metadata such as `VERSION`.
 define_names! {
     BALLOTS => "ballots";
     PROPOSALS_HASHES => "proposals_hashes";
     VOTES => "votes";
+    DEADLINES => "deadlines";
+    ORDINALS_BY_DEADLINE => "ordinals_by_deadline";
+    BY_AUTHOR => "by_author";
+    BY_VOTER => "by_voter";
+    ANSWERS_BY_VOTER => "answers_by_voter";
+    TALLIES => "tallies";
+    VERSION => "schema_version";
+    CONFIG => "config";
+    CONFIG_PROPOSALS => "config_proposals";
+    VOTERS => "voters";
+    ELECTORATES => "electorates";
+    FINALIZATIONS => "finalizations";
+    BY_VALIDATOR_CONFIG => "by_validator_config";
+    LAST_BALLOT_HEIGHTS => "last_ballot_heights";
+    SPONSORSHIPS => "sponsorships";
+    REVISIONS => "revisions";
+    REVISION_ROOTS => "revision_roots";
+    ANSWERS => "answers";
+    BY_CATEGORY => "by_category";
+    BY_TAG => "by_tag";
+    ATTACHMENTS => "attachments";
+    OPEN_BALLOTS => "open_ballots";
 }
 
+/// Position of the `ballot.tallies` root in `Schema::state_hash`.
+pub const TALLIES_TABLE_INDEX: usize = 2;
+
 lazy_static! {
     static ref NO_VOTE_BYTES: Vec<u8> = Vote::new_with_signature(
         &PublicKey::zero(),
@@ -29,80 +66,1109 @@ lazy_static! {
     ).into_bytes();
 }
 
-/// json example:
-/// {"proposals": [{"id": 1, subject: "lina", "description": "example"}]}
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
-struct Proposal {
-    id: u64,
-    subject: String,
-    description: String,
+/// json example:
+/// {"proposals": [{"id": 1, subject: "lina", "description": "example"}]}
+///
+/// `action` is optional and left out of the json when absent, so ballots posted
+/// before actions existed keep their proposals hash:
+/// {"id": 2, "subject": "eve", "description": "example",
+///  "action": {"type": "add_voter", "key": "..."}}
+///
+/// `attachments` anchor documents kept off the chain, it's left out of the json when
+/// empty:
+/// {"id": 3, "subject": "bob", "description": "example",
+///  "attachments": [{"hash": "...", "size": 5120, "mime_type": "application/pdf"}]}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Proposal {
+    id: u64,
+    subject: String,
+    description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    action: Option<ProposalAction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<Attachment>,
+}
+
+impl Proposal {
+    pub fn new(id: u64, subject: &str, description: &str) -> Proposal {
+        Proposal {
+            id,
+            subject: subject.to_string(),
+            description: description.to_string(),
+            action: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn action(&self) -> Option<&ProposalAction> {
+        self.action.as_ref()
+    }
+
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+}
+
+/// Document of a proposal kept off the chain, only its content hash, size and mime type
+/// are posted. Nodes serve the content from their `BlobStore`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Attachment {
+    pub hash: Hash,
+    pub size: u64,
+    pub mime_type: String,
+}
+
+impl CryptoHash for Attachment {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
+}
+
+impl StorageValue for Attachment {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Change applied by `FinalizeBallot` when its proposal wins the ballot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProposalAction {
+    UpdateConfig { config: BallotConfig },
+    AddVoter { key: PublicKey },
+    RemoveVoter { key: PublicKey },
+    /// Proposes `validator_keys` to the configuration service as the next validator
+    /// set, starting `activation_delay` blocks after the ballot is finalized.
+    ChangeValidators {
+        validator_keys: Vec<ValidatorKeys>,
+        activation_delay: u64,
+    },
+}
+
+/// `min_sponsors` is the number of voters besides the author that have to sponsor the
+/// ballot before it accepts votes. `discussion_until` opens a discussion window up to
+/// and including that height, the author may amend the proposals during it and votes
+/// are accepted after it. `nominations_until` opens a nomination phase the same way,
+/// voters may add proposals with `Nominate` during it. All three are left out of the
+/// json when absent so lists without them keep their hash.
+///
+/// A ballot with `questions` leaves `proposals` empty and is answered with
+/// `VoteQuestions`, the questions are left out of the json when there are none:
+/// {"id": 3, "deadline": 100, "proposals": [], "questions": [{"id": 1, "subject": "budget",
+///  "mode": {"type": "single"}, "proposals": [...]}]}
+///
+/// The metadata fields `title`, `description`, `category`, `tags`, `reference` and
+/// `parent` are optional too and left out of the json when absent. `category` and
+/// `tags` are indexed for the api filters, `parent` is the proposals hash of a posted
+/// ballot:
+/// {"id": 4, "deadline": 100, "proposals": [...], "title": "Budget 2019",
+///  "category": "finance", "tags": ["budget", "yearly"],
+///  "reference": {"type": "url", "url": "https://example.com/budget"}, "parent": "..."}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposalList {
+    id: u64,
+    deadline: u64,
+    proposals: Vec<Proposal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_sponsors: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    discussion_until: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nominations_until: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    questions: Vec<Question>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reference: Option<Reference>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<Hash>,
+}
+
+/// Material outside the chain a ballot refers to, by url or by the hash of a document.
+///
+/// json example:
+/// {"type": "url", "url": "https://example.com/budget"}
+/// {"type": "document", "hash": "..."}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Reference {
+    Url { url: String },
+    Document { hash: Hash },
+}
+
+/// First id that occurs more than once in `proposals`.
+fn duplicate_id(proposals: &[Proposal]) -> Option<u64> {
+    for proposal in proposals.iter() {
+        // unique id only splits list into two parts
+        let mut iter = proposals.split(|p| p.id == proposal.id);
+        iter.next();
+        iter.next();
+        if !iter.next().is_none() {
+            return Some(proposal.id);
+        }
+    }
+    None
+}
+
+impl ProposalList {
+    pub fn try_serialize(&self) -> Result<Vec<u8>, JsonError> {
+        serde_json::to_vec(self)
+    }
+
+    pub fn try_deserialize(serialized: &[u8]) -> Result<ProposalList, JsonError> {
+        serde_json::from_slice::<ProposalList>(serialized)
+    }
+
+    pub fn has_duplicate_id(&self) -> bool {
+        self.duplicate_id().is_some()
+    }
+
+    pub fn duplicate_id(&self) -> Option<u64> {
+        duplicate_id(&self.proposals)
+    }
+
+    pub fn contains(&self, id: u64, subject: &str) -> bool {
+        for proposal in self.proposals.iter() {
+            if proposal.id == id && proposal.subject == subject {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    pub fn proposals(&self) -> &[Proposal] {
+        &self.proposals
+    }
+
+    pub fn proposal(&self, id: u64) -> Option<&Proposal> {
+        self.proposals.iter().find(|proposal| proposal.id == id)
+    }
+
+    pub fn min_sponsors(&self) -> u32 {
+        self.min_sponsors.unwrap_or(0)
+    }
+
+    pub fn discussion_until(&self) -> Option<u64> {
+        self.discussion_until
+    }
+
+    pub fn nominations_until(&self) -> Option<u64> {
+        self.nominations_until
+    }
+
+    pub fn questions(&self) -> &[Question] {
+        &self.questions
+    }
+
+    pub fn question(&self, id: u64) -> Option<&Question> {
+        self.questions.iter().find(|question| question.id == id)
+    }
+
+    pub fn has_questions(&self) -> bool {
+        !self.questions.is_empty()
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_ref().map(String::as_str)
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_ref().map(String::as_str)
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_ref().map(String::as_str)
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn reference(&self) -> Option<&Reference> {
+        self.reference.as_ref()
+    }
+
+    pub fn parent(&self) -> Option<&Hash> {
+        self.parent.as_ref()
+    }
+
+    /// Whether the discussion goes on in the block after `latest_height`.
+    pub fn in_discussion(&self, latest_height: Height) -> bool {
+        self.discussion_until
+            .map_or(false, |until| latest_height <= Height(until))
+    }
+
+    /// Whether nominations are accepted in the block after `latest_height`.
+    pub fn in_nominations(&self, latest_height: Height) -> bool {
+        self.nominations_until
+            .map_or(false, |until| latest_height <= Height(until))
+    }
+
+    /// Id the service assigns to the next nominated proposal.
+    pub fn next_id(&self) -> u64 {
+        self.proposals
+            .iter()
+            .map(|proposal| proposal.id)
+            .max()
+            .map_or(1, |id| id.saturating_add(1))
+    }
+
+    /// The list with `amendment` applied, `None` if it edits or withdraws a proposal
+    /// the list doesn't have. Added ids aren't checked, see `duplicate_id`.
+    pub fn amended(&self, amendment: &Amendment) -> Option<ProposalList> {
+        let mut amended = self.clone();
+        match *amendment {
+            Amendment::Add { ref proposal } => amended.proposals.push(proposal.clone()),
+            Amendment::Edit { ref proposal } => {
+                let position = self.position(proposal.id)?;
+                amended.proposals[position] = proposal.clone();
+            }
+            Amendment::Withdraw { proposal_id } => {
+                let position = self.position(proposal_id)?;
+                amended.proposals.remove(position);
+            }
+        }
+        Some(amended)
+    }
+
+    fn position(&self, id: u64) -> Option<usize> {
+        self.proposals.iter().position(|proposal| proposal.id == id)
+    }
+}
+
+/// How voters answer a question: `single` picks one proposal, `approval` picks up to
+/// `max_choices` distinct proposals and each of them gets a vote.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VotingMode {
+    Single,
+    Approval { max_choices: u32 },
+}
+
+impl Default for VotingMode {
+    fn default() -> VotingMode {
+        VotingMode::Single
+    }
+}
+
+/// Independent question of a ballot with its own proposals, voting mode and tally,
+/// see `question_tally_key`. `mode` defaults to `single`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Question {
+    id: u64,
+    subject: String,
+    #[serde(default)]
+    mode: VotingMode,
+    proposals: Vec<Proposal>,
+}
+
+impl Question {
+    pub fn new(id: u64, subject: &str, mode: VotingMode, proposals: Vec<Proposal>) -> Question {
+        Question {
+            id,
+            subject: subject.to_string(),
+            mode,
+            proposals,
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn mode(&self) -> VotingMode {
+        self.mode
+    }
+
+    pub fn proposals(&self) -> &[Proposal] {
+        &self.proposals
+    }
+
+    pub fn duplicate_id(&self) -> Option<u64> {
+        duplicate_id(&self.proposals)
+    }
+
+    /// Describes what's wrong with `proposal_ids` as an answer to the question, if
+    /// anything.
+    pub fn check_answer(&self, proposal_ids: &[u64]) -> Result<(), String> {
+        let max_choices = match self.mode {
+            VotingMode::Single => 1,
+            VotingMode::Approval { max_choices } => u64::from(max_choices),
+        };
+        if proposal_ids.is_empty() || proposal_ids.len() as u64 > max_choices {
+            Err(format!(
+                "question {} takes 1 to {} proposals, got {}",
+                self.id,
+                max_choices,
+                proposal_ids.len()
+            ))?
+        }
+        for (i, proposal_id) in proposal_ids.iter().enumerate() {
+            if proposal_ids[..i].contains(proposal_id) {
+                Err(format!("question {} repeats proposal {}", self.id, proposal_id))?
+            }
+            if !self.proposals.iter().any(|proposal| proposal.id == *proposal_id) {
+                Err(format!("question {} has no proposal {}", self.id, proposal_id))?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Key of a question's tally in `Schema::tallies`.
+pub fn question_tally_key(proposals_hash: &Hash, question_id: u64) -> Hash {
+    let mut bytes = proposals_hash.as_ref().to_vec();
+    bytes.extend_from_slice(&question_id.into_bytes());
+    crypto::hash(&bytes)
+}
+
+/// Key of a voter's answers in `Schema::answers`.
+pub fn answer_sheet_key(proposals_hash: &Hash, voter: &PublicKey) -> Hash {
+    let mut bytes = proposals_hash.as_ref().to_vec();
+    bytes.extend_from_slice(voter.as_ref());
+    crypto::hash(&bytes)
+}
+
+/// Proposals chosen for one question.
+///
+/// json example:
+/// {"question_id": 1, "proposal_ids": [2]}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Answer {
+    pub question_id: u64,
+    pub proposal_ids: Vec<u64>,
+}
+
+impl Answer {
+    pub fn try_deserialize_list(serialized: &[u8]) -> Result<Vec<Answer>, JsonError> {
+        serde_json::from_slice(serialized)
+    }
+}
+
+/// Answers of a voter to every question of a ballot, sent with `VoteQuestions` and
+/// committed in the block at `height`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AnswerSheet {
+    pub from: PublicKey,
+    pub height: u64,
+    pub answers: Vec<Answer>,
+}
+
+impl CryptoHash for AnswerSheet {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
+}
+
+impl StorageValue for AnswerSheet {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Change of a ballot's proposals sent by its author with `AmendBallot`. Nominations
+/// are recorded as `add` with the id assigned by the service.
+///
+/// json example:
+/// {"type": "edit", "proposal": {"id": 1, "subject": "lina", "description": "fixed"}}
+/// {"type": "withdraw", "proposal_id": 2}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Amendment {
+    Add { proposal: Proposal },
+    Edit { proposal: Proposal },
+    Withdraw { proposal_id: u64 },
+}
+
+impl Amendment {
+    pub fn try_deserialize(serialized: &[u8]) -> Result<Amendment, JsonError> {
+        serde_json::from_slice(serialized)
+    }
+
+    pub fn proposal_id(&self) -> u64 {
+        match *self {
+            Amendment::Add { ref proposal } | Amendment::Edit { ref proposal } => proposal.id,
+            Amendment::Withdraw { proposal_id } => proposal_id,
+        }
+    }
+}
+
+/// Proposals of a ballot after an amendment or a nomination sent by `from`, `hash` is
+/// their `ProposalList` hash.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Revision {
+    pub height: u64,
+    pub from: PublicKey,
+    pub amendment: Amendment,
+    pub hash: Hash,
+    pub proposals: ProposalList,
+}
+
+impl CryptoHash for Revision {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
+}
+
+impl StorageValue for Revision {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Revisions of a ballot, oldest first, as served by the API. The posted list is the
+/// revision before the first one, the last one is frozen once the discussion and
+/// nominations end.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RevisionHistory {
+    revisions: Vec<Revision>,
+}
+
+impl RevisionHistory {
+    pub fn new(revisions: Vec<Revision>) -> RevisionHistory {
+        RevisionHistory { revisions }
+    }
+
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+
+    pub fn latest(&self) -> Option<&Revision> {
+        self.revisions.last()
+    }
+}
+
+impl CryptoHash for ProposalList {
+    fn hash(&self) -> Hash {
+        let vec_bytes = self.try_serialize().unwrap();
+        crypto::hash(&vec_bytes)
+    }
+}
+
+impl StorageValue for ProposalList {
+    fn into_bytes(self) -> Vec<u8> {
+        self.try_serialize().unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self::try_deserialize(bytes.as_ref()).unwrap()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposalCount {
+    proposal_id: u64,
+    votes: u64,
+}
+
+impl ProposalCount {
+    pub fn proposal_id(&self) -> u64 {
+        self.proposal_id
+    }
+
+    pub fn votes(&self) -> u64 {
+        self.votes
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Outcome {
+    NoVotes,
+    Winner { proposal_id: u64 },
+    Tie { proposal_ids: Vec<u64> },
+}
+
+/// Votes per proposal, in the order of the ballot's proposals.
+///
+/// json example:
+/// {"counts": [{"proposal_id": 1, "votes": 2}, {"proposal_id": 2, "votes": 0}]}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Tally {
+    counts: Vec<ProposalCount>,
+}
+
+impl Tally {
+    pub fn new(proposals: &ProposalList) -> Tally {
+        Tally::with_proposals(proposals.proposals())
+    }
+
+    pub fn for_question(question: &Question) -> Tally {
+        Tally::with_proposals(question.proposals())
+    }
+
+    fn with_proposals(proposals: &[Proposal]) -> Tally {
+        let counts = proposals
+            .iter()
+            .map(|proposal| ProposalCount {
+                proposal_id: proposal.id(),
+                votes: 0,
+            })
+            .collect();
+        Tally { counts }
+    }
+
+    pub fn from_votes(proposals: &ProposalList, votes: &[Option<Vote>]) -> Tally {
+        let mut tally = Tally::new(proposals);
+        for vote in votes.iter().filter_map(Option::as_ref) {
+            tally.add_vote(vote.proposal_id());
+        }
+        tally
+    }
+
+    pub(crate) fn add_vote(&mut self, proposal_id: u64) {
+        if let Some(count) = self.counts
+            .iter_mut()
+            .find(|count| count.proposal_id == proposal_id)
+        {
+            count.votes += 1;
+        }
+    }
+
+    pub fn counts(&self) -> &[ProposalCount] {
+        &self.counts
+    }
+
+    pub fn votes(&self, proposal_id: u64) -> u64 {
+        self.counts
+            .iter()
+            .find(|count| count.proposal_id == proposal_id)
+            .map_or(0, |count| count.votes)
+    }
+
+    pub fn votes_cast(&self) -> u64 {
+        self.counts.iter().map(|count| count.votes).sum()
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        let max = self.counts.iter().map(|count| count.votes).max().unwrap_or(0);
+        if max == 0 {
+            return Outcome::NoVotes;
+        }
+
+        let mut proposal_ids = self.counts
+            .iter()
+            .filter(|count| count.votes == max)
+            .map(|count| count.proposal_id)
+            .collect::<Vec<_>>();
+        if proposal_ids.len() == 1 {
+            Outcome::Winner {
+                proposal_id: proposal_ids.remove(0),
+            }
+        } else {
+            Outcome::Tie { proposal_ids }
+        }
+    }
+}
+
+impl CryptoHash for Tally {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
+}
+
+impl StorageValue for Tally {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Content and rate limits checked by `Ballot::precheck`, string lengths are in bytes
+/// and distances in blocks from the latest committed height.
+///
+/// `max_open_ballots` caps the ballots of one author that accept votes at the same
+/// time, `min_ballot_interval` is the number of blocks between two ballots of one
+/// author, zero allows several in one block. `max_revisions` caps the amendments and
+/// nominations of one ballot, zero disables both, and `max_nominations_per_voter` caps
+/// the nominations of one voter other than the author. `max_attachment_size` caps the
+/// size of each attachment in bytes. These are omitted from the json while they keep
+/// their defaults, so limits stored before them keep their hash.
+///
+/// json example:
+/// {"max_proposals": 64, "max_subject_len": 256, "max_description_len": 4096,
+///  "min_deadline_distance": 1, "max_deadline_distance": 1000000,
+///  "max_open_ballots": 16, "min_ballot_interval": 0, "max_revisions": 32,
+///  "max_nominations_per_voter": 4, "max_attachment_size": 16777216}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BallotLimits {
+    pub max_proposals: u32,
+    pub max_subject_len: u32,
+    pub max_description_len: u32,
+    pub min_deadline_distance: u64,
+    pub max_deadline_distance: u64,
+    #[serde(skip_serializing_if = "is_default_max_open_ballots")]
+    pub max_open_ballots: u32,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub min_ballot_interval: u64,
+    #[serde(skip_serializing_if = "is_default_max_revisions")]
+    pub max_revisions: u32,
+    #[serde(skip_serializing_if = "is_default_max_nominations_per_voter")]
+    pub max_nominations_per_voter: u32,
+    #[serde(skip_serializing_if = "is_default_max_attachment_size")]
+    pub max_attachment_size: u64,
+}
+
+const DEFAULT_MAX_OPEN_BALLOTS: u32 = 16;
+const DEFAULT_MAX_REVISIONS: u32 = 32;
+const DEFAULT_MAX_NOMINATIONS_PER_VOTER: u32 = 4;
+const DEFAULT_MAX_ATTACHMENT_SIZE: u64 = 1 << 24;
+
+fn is_default_max_open_ballots(value: &u32) -> bool {
+    *value == DEFAULT_MAX_OPEN_BALLOTS
+}
+
+fn is_default_max_revisions(value: &u32) -> bool {
+    *value == DEFAULT_MAX_REVISIONS
+}
+
+fn is_default_max_nominations_per_voter(value: &u32) -> bool {
+    *value == DEFAULT_MAX_NOMINATIONS_PER_VOTER
+}
+
+fn is_default_max_attachment_size(value: &u64) -> bool {
+    *value == DEFAULT_MAX_ATTACHMENT_SIZE
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+impl Default for BallotLimits {
+    fn default() -> BallotLimits {
+        BallotLimits {
+            max_proposals: 64,
+            max_subject_len: 256,
+            max_description_len: 4096,
+            min_deadline_distance: 1,
+            max_deadline_distance: 1_000_000,
+            max_open_ballots: DEFAULT_MAX_OPEN_BALLOTS,
+            min_ballot_interval: 0,
+            max_revisions: DEFAULT_MAX_REVISIONS,
+            max_nominations_per_voter: DEFAULT_MAX_NOMINATIONS_PER_VOTER,
+            max_attachment_size: DEFAULT_MAX_ATTACHMENT_SIZE,
+        }
+    }
+}
+
+/// Largest denominator of a valid `Threshold`.
+pub const MAX_THRESHOLD_DENOMINATOR: u64 = 1000;
+
+/// Share of a total that has to be exceeded, `2/3` requires more than two thirds.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Threshold {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl Threshold {
+    pub fn two_thirds() -> Threshold {
+        Threshold {
+            numerator: 2,
+            denominator: 3,
+        }
+    }
+
+    /// Smallest count exceeding the share of `total`.
+    pub fn required(&self, total: u64) -> u64 {
+        // Split so that `total * numerator` can't overflow.
+        let whole = total / self.denominator * self.numerator;
+        let rest = total % self.denominator * self.numerator / self.denominator;
+        whole + rest + 1
+    }
+
+    /// Whether the share is a fraction below 1 with a denominator up to
+    /// `MAX_THRESHOLD_DENOMINATOR`.
+    pub fn is_valid(&self) -> bool {
+        self.denominator != 0
+            && self.denominator <= MAX_THRESHOLD_DENOMINATOR
+            && self.numerator < self.denominator
+    }
+}
+
+/// Service configuration, set at genesis by `Service::initialize` and changed by
+/// `ProposeConfig` and `VoteConfig` transactions.
+///
+/// json example:
+/// {"limits": {...}, "quorum": {"numerator": 2, "denominator": 3},
+///  "governance": {"numerator": 2, "denominator": 3}}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BallotConfig {
+    pub limits: BallotLimits,
+    /// Share of a ballot's electorate that has to vote for the quorum.
+    pub quorum: Threshold,
+    /// Share of validators that has to approve a configuration change.
+    pub governance: Threshold,
+}
+
+impl Default for BallotConfig {
+    fn default() -> BallotConfig {
+        BallotConfig {
+            limits: BallotLimits::default(),
+            quorum: Threshold::two_thirds(),
+            governance: Threshold::two_thirds(),
+        }
+    }
+}
+
+impl BallotConfig {
+    pub fn try_deserialize(serialized: &[u8]) -> Result<BallotConfig, JsonError> {
+        serde_json::from_slice(serialized)
+    }
+
+    /// Describes the first inconsistent setting, if any.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.quorum.is_valid() {
+            Err(format!(
+                "quorum must be a fraction below 1 with a denominator up to {}",
+                MAX_THRESHOLD_DENOMINATOR
+            ))?
+        }
+        if !self.governance.is_valid() {
+            Err(format!(
+                "governance must be a fraction below 1 with a denominator up to {}",
+                MAX_THRESHOLD_DENOMINATOR
+            ))?
+        }
+        if self.limits.max_proposals == 0 {
+            Err("max_proposals must be positive".to_string())?
+        }
+        if self.limits.min_deadline_distance > self.limits.max_deadline_distance {
+            Err("min_deadline_distance exceeds max_deadline_distance".to_string())?
+        }
+        if self.limits.max_open_ballots == 0 {
+            Err("max_open_ballots must be positive".to_string())?
+        }
+        Ok(())
+    }
+}
+
+impl CryptoHash for BallotConfig {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
 }
 
+impl StorageValue for BallotConfig {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Proposed configuration change with the validators that approved it, the
+/// proposer included. Applied once approvals exceed the governance threshold.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
-pub struct ProposalList {
-    id: u64,
-    deadline: u64,
-    proposals: Vec<Proposal>,
+pub struct ConfigProposal {
+    pub config: BallotConfig,
+    pub approvals: Vec<PublicKey>,
+    /// Height of the block that applied the change.
+    pub applied_at: Option<u64>,
 }
 
-impl ProposalList {
-    pub fn try_serialize(&self) -> Result<Vec<u8>, JsonError> {
-        serde_json::to_vec(self)
+impl CryptoHash for ConfigProposal {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
     }
+}
 
-    pub fn try_deserialize(serialized: &[u8]) -> Result<ProposalList, JsonError> {
-        serde_json::from_slice::<ProposalList>(serialized)
+impl StorageValue for ConfigProposal {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
     }
 
-    pub fn has_duplicate_id(&self) -> bool {
-        for proposal in self.proposals.iter() {
-            // unique id only splits list into two parts
-            let mut iter = self.proposals.split(|p| p.id == proposal.id);
-            iter.next();
-            iter.next();
-            if !iter.next().is_none() {
-                return true;
-            }
-        }
-        false
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
     }
+}
 
-    pub fn contains(&self, id: u64, subject: &str) -> bool {
-        for proposal in self.proposals.iter() {
-            if proposal.id == id && proposal.subject == subject {
-                return true;
-            }
+/// Keys allowed to vote on a ballot, fixed when the ballot is posted. The vote of
+/// a key is stored at its position in the list.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Electorate {
+    voters: Vec<PublicKey>,
+}
+
+impl Electorate {
+    pub fn new(voters: Vec<PublicKey>) -> Electorate {
+        Electorate { voters }
+    }
+
+    pub fn voters(&self) -> &[PublicKey] {
+        &self.voters
+    }
+
+    pub fn position(&self, key: &PublicKey) -> Option<usize> {
+        self.voters.iter().position(|voter| voter == key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.voters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voters.is_empty()
+    }
+}
+
+impl CryptoHash for Electorate {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
+}
+
+impl StorageValue for Electorate {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Sponsors of a ballot posted with `min_sponsors`, in the order they sponsored it.
+/// `opened_at` is the height of the block where the last needed sponsor came in.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Sponsorship {
+    pub min_sponsors: u32,
+    pub sponsors: Vec<PublicKey>,
+    pub opened_at: Option<u64>,
+}
+
+impl Sponsorship {
+    pub fn new(min_sponsors: u32) -> Sponsorship {
+        Sponsorship {
+            min_sponsors,
+            sponsors: Vec::new(),
+            opened_at: None,
         }
-        false
     }
 
-    pub fn deadline(&self) -> u64 {
-        self.deadline
+    pub fn is_open(&self) -> bool {
+        self.opened_at.is_some()
     }
 }
 
-impl CryptoHash for ProposalList {
+impl CryptoHash for Sponsorship {
     fn hash(&self) -> Hash {
-        let vec_bytes = self.try_serialize().unwrap();
-        crypto::hash(&vec_bytes)
+        crypto::hash(&serde_json::to_vec(self).unwrap())
     }
 }
 
-impl StorageValue for ProposalList {
+impl StorageValue for Sponsorship {
     fn into_bytes(self) -> Vec<u8> {
-        self.try_serialize().unwrap()
+        serde_json::to_vec(&self).unwrap()
     }
 
     fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        Self::try_deserialize(bytes.as_ref()).unwrap()
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Deadlines of an author's ballots that may still be open, checked against
+/// `BallotLimits::max_open_ballots`. Closed ones are dropped when the author posts
+/// the next ballot, so the list stays within the limit.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct OpenBallots {
+    pub deadlines: Vec<u64>,
+}
+
+impl OpenBallots {
+    /// Number of the ballots that aren't closed after `latest_height`.
+    pub fn count(&self, latest_height: Height) -> u64 {
+        self.deadlines
+            .iter()
+            .filter(|&&deadline| BallotStatus::at(deadline, latest_height) == BallotStatus::Open)
+            .count() as u64
+    }
+
+    /// Adds a ballot posted in the block after `latest_height`.
+    pub fn push(&mut self, deadline: u64, latest_height: Height) {
+        self.deadlines
+            .retain(|&deadline| BallotStatus::at(deadline, latest_height) == BallotStatus::Open);
+        self.deadlines.push(deadline);
+    }
+}
+
+impl CryptoHash for OpenBallots {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
+}
+
+impl StorageValue for OpenBallots {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
+    }
+}
+
+/// Result of a ballot recorded by `FinalizeBallot`. `action` is the action of the
+/// winning proposal if it was applied, which needs the quorum, a single winner and
+/// an action that still changes something.
+///
+/// `quorum` is the number of votes the quorum required at finalization. Finalizations
+/// recorded before it was kept leave it out of the json, as does `validator_config`.
+///
+/// `validator_config` is the configuration built for an applied `ChangeValidators`,
+/// validators send it to the configuration service from `handle_commit`. It's left
+/// out of the json when absent, so earlier finalizations keep their hash.
+///
+/// Ballots with questions have no winning action, `outcome` is `no_votes` and
+/// `questions` holds the outcome of each question. It's left out of the json when
+/// empty.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Finalization {
+    pub height: u64,
+    pub outcome: Outcome,
+    pub quorum_reached: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum: Option<u64>,
+    pub action: Option<ProposalAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validator_config: Option<StoredConfiguration>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub questions: Vec<QuestionOutcome>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct QuestionOutcome {
+    pub question_id: u64,
+    pub outcome: Outcome,
+}
+
+impl CryptoHash for Finalization {
+    fn hash(&self) -> Hash {
+        crypto::hash(&serde_json::to_vec(self).unwrap())
+    }
+}
+
+impl StorageValue for Finalization {
+    fn into_bytes(self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        serde_json::from_slice(bytes.as_ref()).unwrap()
     }
 }
 
 encoding_struct! {
+    /// `electorate_size` is the number of vote slots reserved when the ballot was posted,
+    /// `votes_cast` the number of them filled so far. Both are rewritten together with
+    /// `votes_history_hash` on every vote.
+    ///
+    /// The two u32 fields take the place of the former u64 `num_voters`, so ballots
+    /// stored before the split keep decoding. Their counts are wrong until the next
+    /// vote or the finalization recounts them, see `Vote::save` and `FinalizeBallot`.
     struct BallotData {
         tx_ballot: Ballot,
         votes_history_hash: &Hash,
-        num_voters: u64,
+        electorate_size: u32,
+        votes_cast: u32,
+    }
+}
+
+encoding_struct! {
+    /// Accepted vote with the height of the block it was committed in.
+    struct VoteRecord {
+        tx_vote: Vote,
+        height: u64,
+    }
+}
+
+/// Ballots accept votes up to and including the block at their deadline. Ballots
+/// posted with `nominations_until` or `discussion_until` are nominating or in
+/// discussion up to that height, ballots posted with `min_sponsors` are seeking
+/// sponsors until enough of them sponsor, and close without opening if the deadline
+/// comes first.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BallotStatus {
+    Open,
+    Closed,
+    SeekingSponsors,
+    InDiscussion,
+    Nominating,
+}
+
+impl ::std::str::FromStr for BallotStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<BallotStatus, String> {
+        match s {
+            "open" => Ok(BallotStatus::Open),
+            "closed" => Ok(BallotStatus::Closed),
+            "seeking_sponsors" => Ok(BallotStatus::SeekingSponsors),
+            "in_discussion" => Ok(BallotStatus::InDiscussion),
+            "nominating" => Ok(BallotStatus::Nominating),
+            _ => Err(format!("unknown ballot status: {}", s)),
+        }
+    }
+}
+
+impl BallotStatus {
+    /// Status by the deadline alone, `Open` stands for every status but `Closed`.
+    pub fn at(deadline: u64, latest_height: Height) -> BallotStatus {
+        if latest_height > Height(deadline) {
+            BallotStatus::Closed
+        } else {
+            BallotStatus::Open
+        }
     }
 }
 
@@ -119,6 +1185,12 @@ impl MaybeVote {
     }
 }
 
+impl From<Option<Vote>> for MaybeVote {
+    fn from(vote: Option<Vote>) -> MaybeVote {
+        MaybeVote(vote)
+    }
+}
+
 impl From<MaybeVote> for Option<Vote> {
     fn from(vote: MaybeVote) -> Option<Vote> {
         vote.0
@@ -183,6 +1255,41 @@ impl<T: AsRef<Snapshot>> Schema<T> {
             .into()
     }
 
+    /// Current proposals of the ballot: the latest revision if it was amended, the
+    /// posted list otherwise.
+    pub fn proposals(&self, proposals_hash: &Hash) -> Option<ProposalList> {
+        if let Some(revision) = self.revisions(proposals_hash).last() {
+            return Some(revision.proposals);
+        }
+        let ballot = self.ballot(proposals_hash)?;
+        ProposalList::try_deserialize(ballot.proposals().as_bytes()).ok()
+    }
+
+    pub fn ballot_status(&self, proposals_hash: &Hash) -> Option<BallotStatus> {
+        let proposals = self.proposals(proposals_hash)?;
+        let latest_height = CoreSchema::new(self.view.as_ref()).height();
+        let status = match BallotStatus::at(proposals.deadline(), latest_height) {
+            BallotStatus::Open if proposals.in_nominations(latest_height) => {
+                BallotStatus::Nominating
+            }
+            BallotStatus::Open if proposals.in_discussion(latest_height) => {
+                BallotStatus::InDiscussion
+            }
+            BallotStatus::Open if !self.is_opened(proposals_hash) => {
+                BallotStatus::SeekingSponsors
+            }
+            status => status,
+        };
+        Some(status)
+    }
+
+    /// Whether the ballot has the sponsors it needs, always true without `min_sponsors`.
+    pub fn is_opened(&self, proposals_hash: &Hash) -> bool {
+        self.sponsorships()
+            .get(proposals_hash)
+            .map_or(true, |sponsorship| sponsorship.is_open())
+    }
+
     pub fn votes_by_proposals_hash(
         &self,
         proposals_hash: &Hash,
@@ -190,10 +1297,255 @@ impl<T: AsRef<Snapshot>> Schema<T> {
         ProofListIndex::new_in_family(VOTES, proposals_hash, self.view.as_ref())
     }
 
+    /// Distinct deadlines of posted ballots, secondary index.
+    pub fn deadlines(&self) -> KeySetIndex<&Snapshot, u64> {
+        KeySetIndex::new(DEADLINES, self.view.as_ref())
+    }
+
+    /// Ordinals of ballots with the given deadline, secondary index.
+    pub fn ordinals_by_deadline(&self, deadline: u64) -> ListIndex<&Snapshot, u64> {
+        ListIndex::new_in_family(ORDINALS_BY_DEADLINE, &deadline, self.view.as_ref())
+    }
+
+    /// Ordinals of ballots posted by the given author, secondary index.
+    pub fn ordinals_by_author(&self, author: &PublicKey) -> ListIndex<&Snapshot, u64> {
+        ListIndex::new_in_family(BY_AUTHOR, author, self.view.as_ref())
+    }
+
+    /// Ordinals of ballots posted with the given category, secondary index.
+    pub fn ordinals_by_category(&self, category: &str) -> ListIndex<&Snapshot, u64> {
+        ListIndex::new_in_family(BY_CATEGORY, &category.to_string(), self.view.as_ref())
+    }
+
+    /// Ordinals of ballots posted with the given tag, secondary index.
+    pub fn ordinals_by_tag(&self, tag: &str) -> ListIndex<&Snapshot, u64> {
+        ListIndex::new_in_family(BY_TAG, &tag.to_string(), self.view.as_ref())
+    }
+
+    /// Attachments of ballot proposals by content hash, secondary index. A document
+    /// attached more than once keeps the first attachment posted with it.
+    pub fn attachments(&self) -> MapIndex<&Snapshot, Hash, Attachment> {
+        MapIndex::new(ATTACHMENTS, self.view.as_ref())
+    }
+
+    /// Vote counts by proposals hash, kept in step with the vote lists. Questions of a
+    /// ballot have their tallies under `question_tally_key`.
+    pub fn tallies(&self) -> ProofMapIndex<&Snapshot, Hash, Tally> {
+        ProofMapIndex::new(TALLIES, self.view.as_ref())
+    }
+
+    /// Votes cast by the given voter in commit order, secondary index.
+    pub fn votes_by_voter(&self, voter: &PublicKey) -> ListIndex<&Snapshot, VoteRecord> {
+        ListIndex::new_in_family(BY_VOTER, voter, self.view.as_ref())
+    }
+
+    /// Proposals hashes of the ballots the given voter answered with `VoteQuestions`,
+    /// in commit order, secondary index. The answer sheets are in `answers`.
+    pub fn answers_by_voter(&self, voter: &PublicKey) -> ListIndex<&Snapshot, Hash> {
+        ListIndex::new_in_family(ANSWERS_BY_VOTER, voter, self.view.as_ref())
+    }
+
+    /// Layout version of the stored data, absent in databases that predate versioning.
+    /// See `migrations::SCHEMA_VERSION`.
+    pub fn schema_version(&self) -> Entry<&Snapshot, u32> {
+        Entry::new(VERSION, self.view.as_ref())
+    }
+
+    /// Configuration in effect, `BallotConfig::default()` until one is stored.
+    pub fn config(&self) -> BallotConfig {
+        self.config_entry().get().unwrap_or_default()
+    }
+
+    pub fn limits(&self) -> BallotLimits {
+        self.config().limits
+    }
+
+    fn config_entry(&self) -> Entry<&Snapshot, BallotConfig> {
+        Entry::new(CONFIG, self.view.as_ref())
+    }
+
+    /// Configuration proposals by the hash of the proposed `BallotConfig`.
+    pub fn config_proposals(&self) -> ProofMapIndex<&Snapshot, Hash, ConfigProposal> {
+        ProofMapIndex::new(CONFIG_PROPOSALS, self.view.as_ref())
+    }
+
+    /// Voter registry, keys by the height they were added at. Empty until the first
+    /// `AddVoter` or `RemoveVoter` action is applied, see `eligible_voters`.
+    pub fn voters(&self) -> ProofMapIndex<&Snapshot, PublicKey, u64> {
+        ProofMapIndex::new(VOTERS, self.view.as_ref())
+    }
+
+    /// Keys allowed to post ballots and to vote on ballots posted now: the voter
+    /// registry, or the validators while the registry is empty.
+    pub fn eligible_voters(&self) -> Vec<PublicKey> {
+        let voters = self.voters().keys().collect::<Vec<_>>();
+        if !voters.is_empty() {
+            return voters;
+        }
+        CoreSchema::new(self.view.as_ref())
+            .actual_configuration()
+            .validator_keys
+            .iter()
+            .map(|keys| keys.service_key)
+            .collect()
+    }
+
+    pub fn is_voter(&self, key: &PublicKey) -> bool {
+        self.eligible_voters().contains(key)
+    }
+
+    /// Electorates of ballots by proposals hash.
+    pub fn electorates(&self) -> ProofMapIndex<&Snapshot, Hash, Electorate> {
+        ProofMapIndex::new(ELECTORATES, self.view.as_ref())
+    }
+
+    /// Electorate of the ballot. Ballots posted before electorates were stored have
+    /// their vote slots reserved by validator id, their electorate is the validator set
+    /// of the configuration actual at the height of the ballot's block.
+    pub fn electorate(&self, proposals_hash: &Hash) -> Option<Electorate> {
+        if let Some(electorate) = self.electorates().get(proposals_hash) {
+            return Some(electorate);
+        }
+
+        let ballot = self.ballot(proposals_hash)?;
+        let core_schema = CoreSchema::new(self.view.as_ref());
+        let location = core_schema.transactions_locations().get(&ballot.hash())?;
+        let voters = core_schema
+            .configuration_by_height(location.block_height())
+            .validator_keys
+            .iter()
+            .map(|keys| keys.service_key)
+            .collect();
+        Some(Electorate::new(voters))
+    }
+
+    /// Results of finalized ballots by proposals hash.
+    pub fn finalizations(&self) -> ProofMapIndex<&Snapshot, Hash, Finalization> {
+        ProofMapIndex::new(FINALIZATIONS, self.view.as_ref())
+    }
+
+    /// Proposals hashes of finalized ballots by the hash of the validator configuration
+    /// they produced, secondary index.
+    pub fn ballots_by_validator_config(&self) -> MapIndex<&Snapshot, Hash, Hash> {
+        MapIndex::new(BY_VALIDATOR_CONFIG, self.view.as_ref())
+    }
+
+    /// Height of the latest ballot of each author, checked against
+    /// `BallotLimits::min_ballot_interval`. Authors of ballots posted before the index
+    /// existed have no entry until their next ballot.
+    pub fn last_ballot_heights(&self) -> ProofMapIndex<&Snapshot, PublicKey, u64> {
+        ProofMapIndex::new(LAST_BALLOT_HEIGHTS, self.view.as_ref())
+    }
+
+    /// Deadlines of the ballots of each author that may still be open. Ballots posted
+    /// before the index existed aren't in it.
+    pub fn open_ballots(&self) -> ProofMapIndex<&Snapshot, PublicKey, OpenBallots> {
+        ProofMapIndex::new(OPEN_BALLOTS, self.view.as_ref())
+    }
+
+    /// Number of ballots posted by `author` that aren't closed after `latest_height`,
+    /// ballots seeking sponsors included.
+    pub fn open_ballots_by_author(&self, author: &PublicKey, latest_height: Height) -> u64 {
+        self.open_ballots()
+            .get(author)
+            .map_or(0, |open| open.count(latest_height))
+    }
+
+    /// Sponsors of ballots posted with `min_sponsors`, by proposals hash.
+    pub fn sponsorships(&self) -> ProofMapIndex<&Snapshot, Hash, Sponsorship> {
+        ProofMapIndex::new(SPONSORSHIPS, self.view.as_ref())
+    }
+
+    /// Revisions of the ballot with the posted list hashed to `proposals_hash`.
+    pub fn revisions(&self, proposals_hash: &Hash) -> ProofListIndex<&Snapshot, Revision> {
+        ProofListIndex::new_in_family(REVISIONS, proposals_hash, self.view.as_ref())
+    }
+
+    /// Roots of the revision lists by proposals hash, updated on every revision.
+    pub fn revision_roots(&self) -> ProofMapIndex<&Snapshot, Hash, Hash> {
+        ProofMapIndex::new(REVISION_ROOTS, self.view.as_ref())
+    }
+
+    /// Sender of the latest revision that added `proposal_id` to the ballot, `None` if
+    /// the proposal comes from the posted list.
+    pub fn proposal_added_by(&self, proposals_hash: &Hash, proposal_id: u64) -> Option<PublicKey> {
+        self.revisions(proposals_hash)
+            .iter()
+            .filter(|revision| match revision.amendment {
+                Amendment::Add { ref proposal } => proposal.id == proposal_id,
+                _ => false,
+            })
+            .last()
+            .map(|revision| revision.from)
+    }
+
+    /// Revisions of the ballot collected for the API.
+    pub fn revision_history(&self, proposals_hash: &Hash) -> RevisionHistory {
+        RevisionHistory::new(self.revisions(proposals_hash).iter().collect())
+    }
+
+    /// Answer sheets of ballots with questions, by `answer_sheet_key`.
+    pub fn answers(&self) -> ProofMapIndex<&Snapshot, Hash, AnswerSheet> {
+        ProofMapIndex::new(ANSWERS, self.view.as_ref())
+    }
+
+    /// Whether `voter` voted on the ballot, or answered its questions.
+    pub fn has_voted(&self, proposals_hash: &Hash, voter: &PublicKey) -> bool {
+        let voted = self
+            .electorate(proposals_hash)
+            .and_then(|electorate| electorate.position(voter))
+            .and_then(|position| {
+                self.votes_by_proposals_hash(proposals_hash)
+                    .get(position as u64)
+            })
+            .map_or(false, |vote| vote.is_some());
+        voted || self
+            .answers()
+            .contains(&answer_sheet_key(proposals_hash, voter))
+    }
+
+    /// Returns root hashes of the committed indexes, in the following order:
+    ///
+    /// 0. `ballot.ballots`: ballot data by proposals hash. Vote lists (`ballot.votes`
+    ///    family) are committed here, `BallotData::votes_history_hash` is the root
+    ///    of the ballot's vote list and is updated on every vote.
+    /// 1. `ballot.proposals_hashes`: proposals hashes by ordinal.
+    /// 2. `ballot.tallies`: vote counts by proposals hash, see `TALLIES_TABLE_INDEX`.
+    /// 3. `ballot.config`: hash of the configuration in effect.
+    /// 4. `ballot.config_proposals`: configuration proposals by config hash.
+    /// 5. `ballot.voters`: voter registry.
+    /// 6. `ballot.electorates`: electorates by proposals hash.
+    /// 7. `ballot.finalizations`: results of finalized ballots by proposals hash.
+    /// 8. `ballot.last_ballot_heights`: height of the latest ballot by author.
+    /// 9. `ballot.sponsorships`: sponsors of ballots by proposals hash.
+    /// 10. `ballot.revision_roots`: roots of the revision lists (`ballot.revisions`
+    ///     family) by proposals hash.
+    /// 11. `ballot.answers`: answer sheets of ballots with questions.
+    /// 12. `ballot.open_ballots`: deadlines of possibly open ballots by author.
+    ///
+    /// `ballot.deadlines`, `ballot.ordinals_by_deadline`, `ballot.by_author`,
+    /// `ballot.by_category`, `ballot.by_tag`, `ballot.attachments`, `ballot.by_voter`,
+    /// `ballot.answers_by_voter` and `ballot.by_validator_config` are derived from the
+    /// ballots, votes, answers, finalizations and blocks and aren't committed, neither is
+    /// `ballot.schema_version`.
+    ///
+    /// Positions are part of the core `state_hash_aggregator` keys, so new top level
+    /// indexes (tallies included) are appended and never reordered.
     pub fn state_hash(&self) -> Vec<Hash> {
         vec![
             self.ballot_data_by_proposals_hash().merkle_root(),
             self.proposals_hash_by_ordinal().merkle_root(),
+            self.tallies().merkle_root(),
+            self.config_entry().hash(),
+            self.config_proposals().merkle_root(),
+            self.voters().merkle_root(),
+            self.electorates().merkle_root(),
+            self.finalizations().merkle_root(),
+            self.last_ballot_heights().merkle_root(),
+            self.sponsorships().merkle_root(),
+            self.revision_roots().merkle_root(),
+            self.answers().merkle_root(),
+            self.open_ballots().merkle_root(),
         ]
     }
 
@@ -222,4 +1574,108 @@ impl<'a> Schema<&'a mut Fork> {
     ) -> ProofListIndex<&mut Fork, MaybeVote> {
         ProofListIndex::new_in_family(VOTES, proposals_hash, &mut self.view)
     }
+
+    pub(crate) fn deadlines_mut(&mut self) -> KeySetIndex<&mut Fork, u64> {
+        KeySetIndex::new(DEADLINES, &mut self.view)
+    }
+
+    pub(crate) fn ordinals_by_deadline_mut(&mut self, deadline: u64) -> ListIndex<&mut Fork, u64> {
+        ListIndex::new_in_family(ORDINALS_BY_DEADLINE, &deadline, &mut self.view)
+    }
+
+    pub(crate) fn ordinals_by_author_mut(
+        &mut self,
+        author: &PublicKey,
+    ) -> ListIndex<&mut Fork, u64> {
+        ListIndex::new_in_family(BY_AUTHOR, author, &mut self.view)
+    }
+
+    pub(crate) fn ordinals_by_category_mut(
+        &mut self,
+        category: &str,
+    ) -> ListIndex<&mut Fork, u64> {
+        ListIndex::new_in_family(BY_CATEGORY, &category.to_string(), &mut self.view)
+    }
+
+    pub(crate) fn ordinals_by_tag_mut(&mut self, tag: &str) -> ListIndex<&mut Fork, u64> {
+        ListIndex::new_in_family(BY_TAG, &tag.to_string(), &mut self.view)
+    }
+
+    pub(crate) fn attachments_mut(&mut self) -> MapIndex<&mut Fork, Hash, Attachment> {
+        MapIndex::new(ATTACHMENTS, &mut self.view)
+    }
+
+    pub(crate) fn schema_version_mut(&mut self) -> Entry<&mut Fork, u32> {
+        Entry::new(VERSION, &mut self.view)
+    }
+
+    pub(crate) fn config_mut(&mut self) -> Entry<&mut Fork, BallotConfig> {
+        Entry::new(CONFIG, &mut self.view)
+    }
+
+    pub(crate) fn config_proposals_mut(
+        &mut self,
+    ) -> ProofMapIndex<&mut Fork, Hash, ConfigProposal> {
+        ProofMapIndex::new(CONFIG_PROPOSALS, &mut self.view)
+    }
+
+    pub(crate) fn tallies_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Tally> {
+        ProofMapIndex::new(TALLIES, &mut self.view)
+    }
+
+    pub(crate) fn voters_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        ProofMapIndex::new(VOTERS, &mut self.view)
+    }
+
+    pub(crate) fn electorates_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Electorate> {
+        ProofMapIndex::new(ELECTORATES, &mut self.view)
+    }
+
+    pub(crate) fn finalizations_mut(
+        &mut self,
+    ) -> ProofMapIndex<&mut Fork, Hash, Finalization> {
+        ProofMapIndex::new(FINALIZATIONS, &mut self.view)
+    }
+
+    pub(crate) fn last_ballot_heights_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, u64> {
+        ProofMapIndex::new(LAST_BALLOT_HEIGHTS, &mut self.view)
+    }
+
+    pub(crate) fn open_ballots_mut(&mut self) -> ProofMapIndex<&mut Fork, PublicKey, OpenBallots> {
+        ProofMapIndex::new(OPEN_BALLOTS, &mut self.view)
+    }
+
+    pub(crate) fn sponsorships_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Sponsorship> {
+        ProofMapIndex::new(SPONSORSHIPS, &mut self.view)
+    }
+
+    pub(crate) fn revisions_mut(
+        &mut self,
+        proposals_hash: &Hash,
+    ) -> ProofListIndex<&mut Fork, Revision> {
+        ProofListIndex::new_in_family(REVISIONS, proposals_hash, &mut self.view)
+    }
+
+    pub(crate) fn revision_roots_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, Hash> {
+        ProofMapIndex::new(REVISION_ROOTS, &mut self.view)
+    }
+
+    pub(crate) fn answers_mut(&mut self) -> ProofMapIndex<&mut Fork, Hash, AnswerSheet> {
+        ProofMapIndex::new(ANSWERS, &mut self.view)
+    }
+
+    pub(crate) fn ballots_by_validator_config_mut(&mut self) -> MapIndex<&mut Fork, Hash, Hash> {
+        MapIndex::new(BY_VALIDATOR_CONFIG, &mut self.view)
+    }
+
+    pub(crate) fn votes_by_voter_mut(
+        &mut self,
+        voter: &PublicKey,
+    ) -> ListIndex<&mut Fork, VoteRecord> {
+        ListIndex::new_in_family(BY_VOTER, voter, &mut self.view)
+    }
+
+    pub(crate) fn answers_by_voter_mut(&mut self, voter: &PublicKey) -> ListIndex<&mut Fork, Hash> {
+        ListIndex::new_in_family(ANSWERS_BY_VOTER, voter, &mut self.view)
+    }
 }
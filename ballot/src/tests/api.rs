@@ -1,10 +1,18 @@
-use exonum::crypto::{CryptoHash, Hash};
+use exonum::crypto::{self, CryptoHash, Hash};
 use exonum::blockchain::Transaction;
-use exonum_testkit::{ApiKind, TestKit, TestKitApi};
+use exonum::encoding::serialize::encode_hex;
+use exonum::helpers::Height;
+use exonum::messages::Message;
+use exonum_testkit::{ApiKind, TestKit, TestKitApi, TestNode};
 
-use SERVICE_NAME;
+use {SERVICE_ID, SERVICE_NAME};
 use schema::{ProposalList, Schema as BallotSchema};
-use api::{BallotHashInfo, BallotResponse, VoteRequest, VoteResponse, VotesInfo};
+use api::{BallotHashInfo, BallotOrder, BallotPage, BallotQuery, BallotResponse,
+          ParticipationStats, SignedTransaction, TransactionStatus, VoteRequest, VoteResponse};
+use error::{Error as ServiceError, ErrorCode};
+use schema::{BallotConfig, BallotStatus, Outcome, Threshold};
+use client::{BallotClient, ClientError, Transport};
+use transactions::Transactions as BallotTransactions;
 use tests::common::*;
 use tests::tx_logic::BallotTestKit;
 
@@ -30,39 +38,12 @@ fn forge_ballot_hash_info(testkit: &TestKit, proposals: ProposalList) -> BallotH
 }
 
 trait BallotApiTest {
-    fn range_ballots(&self, offset: u64, limit: usize) -> Vec<BallotHashInfo>;
-
-    fn ballot_by_hash(&self, proposals_hash: &Hash) -> BallotHashInfo;
-
-    fn votes_for_ballot(&self, proposals_hash: &Hash) -> VotesInfo;
-
     fn post_ballot(&self, proposals: &ProposalList) -> BallotResponse;
 
     fn post_vote(&self, proposals_hash: &Hash, vote_req: &VoteRequest) -> VoteResponse;
 }
 
 impl BallotApiTest for TestKitApi {
-    fn range_ballots(&self, offset: u64, limit: usize) -> Vec<BallotHashInfo> {
-        self.get(
-            ApiKind::Service(SERVICE_NAME),
-            &format!("/v1/ballots?limit={}&offset={}", limit, offset),
-        )
-    }
-
-    fn ballot_by_hash(&self, proposals_hash: &Hash) -> BallotHashInfo {
-        self.get(
-            ApiKind::Service(SERVICE_NAME),
-            &format!("/v1/ballots/{}", proposals_hash),
-        )
-    }
-
-    fn votes_for_ballot(&self, proposals_hash: &Hash) -> VotesInfo {
-        self.get(
-            ApiKind::Service(SERVICE_NAME),
-            &format!("/v1/ballots/{}/votes", proposals_hash),
-        )
-    }
-
     fn post_ballot(&self, proposals: &ProposalList) -> BallotResponse {
         self.post_private(ApiKind::Service(SERVICE_NAME), "/v1/ballots", proposals)
     }
@@ -76,6 +57,15 @@ impl BallotApiTest for TestKitApi {
     }
 }
 
+fn client(testkit: &TestKit) -> BallotClient<TestKitApi> {
+    BallotClient::new(testkit.api())
+}
+
+fn client_with_keys(testkit: &TestKit, node: &TestNode) -> BallotClient<TestKitApi> {
+    let (public_key, secret_key) = node.service_keypair();
+    BallotClient::with_keys(testkit.api(), *public_key, secret_key.clone())
+}
+
 #[test]
 fn test_ranges_ballots() {
     let mut testkit: TestKit = TestKit::ballot_default();
@@ -100,14 +90,315 @@ fn test_ranges_ballots() {
         .map(|proposals| forge_ballot_hash_info(&testkit, proposals))
         .collect::<Vec<BallotHashInfo>>();
 
-    let resp = testkit.api().range_ballots(0, 10);
-    assert_eq!(ballots_info, resp);
+    let client = client(&testkit);
+    let range = |offset, limit| client.range_ballots(offset, limit).unwrap();
+    assert_eq!(ballots_info, range(0, 10));
+    assert_eq!(ballots_info[1..].to_vec(), range(1, 10));
+    assert_eq!(vec![ballots_info[0].clone()], range(0, 1));
+}
+
+#[test]
+fn test_filter_ballots() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let proposals_list = vec![
+        r#"{"id": 1, "deadline": 5, "proposals": [{"id": 1, "subject": "triss", "description": "magic"}]}"#,
+        r#"{"id": 2, "deadline": 100, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}]}"#,
+        r#"{"id": 3, "deadline": 200, "proposals": [{"id": 1, "subject": "yennefer", "description": "magic"}]}"#,
+    ];
+    let proposals_list = proposals_list
+        .iter()
+        .map(|json| ProposalList::try_deserialize(json.as_bytes()).unwrap())
+        .collect::<Vec<ProposalList>>();
+    let validators = testkit.network().validators().to_vec();
+    let tx_ballot_list = proposals_list
+        .iter()
+        .enumerate()
+        .map(|(idx, proposals)| new_tx_ballot(&validators[idx % 2], proposals.clone()))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_ballot_list);
 
-    let resp = testkit.api().range_ballots(1, 10);
-    assert_eq!(ballots_info[1..].to_vec(), resp);
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "ciri".to_string(),
+    };
+    let tx_vote = new_tx_vote(&validators[3], &proposals_list[1].hash(), &vote_req);
+    testkit.create_block_with_transaction(tx_vote);
+    testkit.create_blocks_until(Height(10));
+
+    let client = client(&testkit);
+    let hashes = |query: BallotQuery| {
+        client
+            .ballots(&query)
+            .unwrap()
+            .ballots
+            .into_iter()
+            .map(|info| info.proposals_hash)
+            .collect::<Vec<_>>()
+    };
+    let proposals_hashes = proposals_list
+        .iter()
+        .map(|proposals| proposals.hash())
+        .collect::<Vec<_>>();
 
-    let resp = testkit.api().range_ballots(0, 1);
-    assert_eq!(vec![ballots_info[0].clone()], resp);
+    assert_eq!(
+        vec![proposals_hashes[2], proposals_hashes[1], proposals_hashes[0]],
+        hashes(BallotQuery {
+            order: Some(BallotOrder::Newest),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        vec![proposals_hashes[1], proposals_hashes[2]],
+        hashes(BallotQuery {
+            status: Some(BallotStatus::Open),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        vec![proposals_hashes[0]],
+        hashes(BallotQuery {
+            status: Some(BallotStatus::Closed),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        vec![proposals_hashes[0], proposals_hashes[1]],
+        hashes(BallotQuery {
+            deadline_from: Some(5),
+            deadline_to: Some(100),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        vec![proposals_hashes[0], proposals_hashes[2]],
+        hashes(BallotQuery {
+            author: Some(*validators[0].service_keypair().0),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        vec![proposals_hashes[2]],
+        hashes(BallotQuery {
+            status: Some(BallotStatus::Open),
+            not_voted_by: Some(*validators[3].service_keypair().0),
+            ..Default::default()
+        })
+    );
+}
+
+#[test]
+fn test_ballots_by_author() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let proposals_list = (1..5)
+        .map(|id| {
+            let json = format!(
+                r#"{{"id": {}, "deadline": 100, "proposals": [{{"id": 1, "subject": "ciri", "description": "queen"}}]}}"#,
+                id
+            );
+            ProposalList::try_deserialize(json.as_bytes()).unwrap()
+        })
+        .collect::<Vec<ProposalList>>();
+    let validators = testkit.network().validators().to_vec();
+    let tx_ballot_list = proposals_list
+        .iter()
+        .enumerate()
+        .map(|(idx, proposals)| new_tx_ballot(&validators[idx % 2], proposals.clone()))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_ballot_list);
+
+    let client = client(&testkit);
+    let author = validators[1].service_keypair().0;
+    let page = client
+        .ballots_by_author(author, &BallotQuery::default())
+        .unwrap();
+    let proposals_hashes = page.ballots
+        .into_iter()
+        .map(|info| info.proposals_hash)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![proposals_list[1].hash(), proposals_list[3].hash()],
+        proposals_hashes
+    );
+
+    // The endpoint as first released on `/v1` serves the same page.
+    let page_v1: BallotPage =
+        Transport::get(client.transport(), &format!("/v1/authors/{}/ballots", author)).unwrap();
+    assert_eq!(
+        proposals_hashes,
+        page_v1
+            .ballots
+            .into_iter()
+            .map(|info| info.proposals_hash)
+            .collect::<Vec<_>>()
+    );
+
+    let author = validators[2].service_keypair().0;
+    let page = client
+        .ballots_by_author(author, &BallotQuery::default())
+        .unwrap();
+    assert!(page.ballots.is_empty());
+}
+
+#[test]
+fn test_filter_ballots_by_category_and_tag() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let budget = ProposalList::try_deserialize(
+        r#"{"id": 1, "deadline": 100, "proposals": [{"id": 1, "subject": "yes", "description": "raise"}],
+            "title": "Budget", "category": "finance", "tags": ["budget", "yearly"]}"#
+            .as_bytes(),
+    ).unwrap();
+    let audit = ProposalList::try_deserialize(
+        format!(
+            r#"{{"id": 2, "deadline": 100, "proposals": [{{"id": 1, "subject": "yes", "description": "audit"}}],
+                "category": "finance", "tags": ["yearly"], "parent": "{}",
+                "reference": {{"type": "url", "url": "https://example.com/audit"}}}}"#,
+            budget.hash().to_hex()
+        ).as_bytes(),
+    ).unwrap();
+    let board = ProposalList::try_deserialize(
+        r#"{"id": 3, "deadline": 100, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}],
+            "category": "board", "tags": ["budget"]}"#
+            .as_bytes(),
+    ).unwrap();
+    let validators = testkit.network().validators().to_vec();
+    testkit.create_block_with_transactions(txvec![
+        new_tx_ballot(&validators[0], budget.clone()),
+        new_tx_ballot(&validators[1], audit.clone()),
+        new_tx_ballot(&validators[2], board.clone()),
+    ]);
+
+    let client = client(&testkit);
+    let hashes = |query: BallotQuery| {
+        client
+            .ballots(&query)
+            .unwrap()
+            .ballots
+            .into_iter()
+            .map(|info| info.proposals_hash)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        vec![budget.hash(), audit.hash()],
+        hashes(BallotQuery {
+            category: Some("finance".to_string()),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        vec![board.hash(), budget.hash()],
+        hashes(BallotQuery {
+            tag: Some("budget".to_string()),
+            order: Some(BallotOrder::Newest),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        vec![budget.hash()],
+        hashes(BallotQuery {
+            category: Some("finance".to_string()),
+            tag: Some("budget".to_string()),
+            ..Default::default()
+        })
+    );
+    assert!(
+        hashes(BallotQuery {
+            tag: Some("archive".to_string()),
+            ..Default::default()
+        }).is_empty()
+    );
+}
+
+#[test]
+fn test_upload_attachment() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let content = b"minutes of the last meeting";
+    let content_hash = crypto::hash(content);
+    let proposals = ProposalList::try_deserialize(
+        format!(
+            r#"{{"id": 1, "deadline": 100, "proposals": [{{"id": 1, "subject": "yes", "description": "approve",
+                "attachments": [{{"hash": "{}", "size": {}, "mime_type": "text/plain"}}]}}]}}"#,
+            content_hash.to_hex(),
+            content.len()
+        ).as_bytes(),
+    ).unwrap();
+    testkit.create_block_with_transaction(new_tx_ballot(
+        &testkit.network().validators()[0],
+        proposals.clone(),
+    ));
+
+    let client = client(&testkit);
+    let status = client.attachment_status(&content_hash).unwrap();
+    assert_eq!(
+        Some(&proposals.proposals()[0].attachments()[0]),
+        status.attachment.as_ref()
+    );
+    assert!(!status.available);
+
+    let outsider = {
+        let (public_key, secret_key) = crypto::gen_keypair();
+        BallotClient::with_keys(testkit.api(), public_key, secret_key)
+    };
+    match outsider.upload_attachment(content) {
+        Err(ClientError::Status(403, _)) => {}
+        other => panic!("Unexpected upload result: {:?}", other),
+    }
+    assert!(!client.attachment_status(&content_hash).unwrap().available);
+
+    let status = client_with_keys(&testkit, &testkit.network().validators()[1])
+        .upload_attachment(content)
+        .unwrap();
+    assert_eq!(content_hash, status.hash);
+    assert!(status.available);
+
+    let unknown = client.attachment_status(&Hash::zero()).unwrap();
+    assert_eq!(None, unknown.attachment);
+    assert!(!unknown.available);
+}
+
+#[test]
+fn test_ballots_cursor_pagination() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let proposals_list = (1..6)
+        .map(|id| {
+            let json = format!(
+                r#"{{"id": {}, "deadline": 100, "proposals": [{{"id": 1, "subject": "triss", "description": "magic"}}]}}"#,
+                id
+            );
+            ProposalList::try_deserialize(json.as_bytes()).unwrap()
+        })
+        .collect::<Vec<ProposalList>>();
+    let tx_ballot_list = proposals_list
+        .iter()
+        .map(|proposals| new_tx_ballot(&testkit.network().validators()[0], proposals.clone()))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_ballot_list);
+
+    let client = client(&testkit);
+    let mut query = BallotQuery {
+        limit: Some(2),
+        order: Some(BallotOrder::Newest),
+        ..Default::default()
+    };
+    let mut proposals_hashes = Vec::new();
+    loop {
+        let page = client.ballots(&query).unwrap();
+        assert!(page.ballots.len() <= 2);
+        proposals_hashes.extend(page.ballots.into_iter().map(|info| info.proposals_hash));
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => break,
+        }
+    }
+
+    let expected = proposals_list
+        .iter()
+        .rev()
+        .map(|proposals| proposals.hash())
+        .collect::<Vec<_>>();
+    assert_eq!(expected, proposals_hashes);
 }
 
 #[test]
@@ -117,7 +408,7 @@ fn test_ballot_by_hash_with_proofs() {
     let proposals_hash = proposals.hash();
     let info = forge_ballot_hash_info(&testkit, proposals);
 
-    let resp = testkit.api().ballot_by_hash(&proposals_hash);
+    let resp = client(&testkit).ballot(&proposals_hash).unwrap();
     assert_eq!(info, resp);
 }
 
@@ -126,16 +417,16 @@ fn test_votes_for_ballot() {
     use exonum::blockchain::Schema;
 
     let mut testkit: TestKit = TestKit::ballot_default();
-    let api = testkit.api();
+    let client = client(&testkit);
 
     let (_, proposals) = new_proposals_data();
     let proposals_hash = proposals.hash();
     let tx_ballot = new_tx_ballot(&testkit.network().validators()[0], proposals);
-    assert_eq!(None, api.votes_for_ballot(&proposals_hash));
+    assert_eq!(None, client.votes(&proposals_hash).unwrap());
     testkit.create_block_with_transaction(tx_ballot);
     assert_eq!(
         Some(vec![None; testkit.network().validators().len()]),
-        api.votes_for_ballot(&proposals_hash)
+        client.votes(&proposals_hash).unwrap()
     );
 
     let vote_req = VoteRequest {
@@ -150,7 +441,9 @@ fn test_votes_for_ballot() {
         .map(|tx| Box::new(tx) as Box<Transaction>)
         .collect::<Vec<_>>();
     testkit.create_block_with_transactions(tx_votes);
-    let resp = api.votes_for_ballot(&proposals_hash)
+    let resp = client
+        .votes(&proposals_hash)
+        .unwrap()
         .expect("Votes for ballot are absent");
     for entry in resp.into_iter().take(testkit.network().validators().len()) {
         let tx = entry.expect("Vote for ballot is absent");
@@ -165,6 +458,166 @@ fn test_votes_for_ballot() {
     }
 }
 
+#[test]
+fn test_ballot_results() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (_, proposals) = create_test_ballot!(testkit);
+    let proposals_hash = proposals.hash();
+
+    let validators = testkit.network().validators().to_vec();
+    let votes = vec![(0, 2, "ciri"), (1, 2, "ciri"), (2, 1, "triss")];
+    let tx_vote_list = votes
+        .into_iter()
+        .map(|(idx, proposal_id, subject)| {
+            let vote_req = VoteRequest {
+                proposal_id,
+                proposal_subject: subject.to_string(),
+            };
+            new_tx_vote(&validators[idx], &proposals_hash, &vote_req)
+        })
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_vote_list);
+
+    let client = client(&testkit);
+    let results = client.results(&proposals_hash).unwrap().unwrap();
+    assert_eq!(BallotStatus::Open, results.status);
+    assert_eq!(
+        vec![1, 2, 0],
+        results
+            .proposals
+            .iter()
+            .map(|proposal| proposal.votes)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(4, results.electorate);
+    assert_eq!(3, results.votes_cast);
+    assert_eq!(3, results.quorum);
+    assert!(results.quorum_reached);
+    assert_eq!(vec![*validators[3].service_keypair().0], results.non_voters);
+    assert_eq!(Outcome::Winner { proposal_id: 2 }, results.outcome);
+    assert!(results.proof.is_none());
+
+    testkit.create_blocks_until(Height(proposals.deadline() + 1));
+    let results = client.results(&proposals_hash).unwrap().unwrap();
+    assert_eq!(BallotStatus::Closed, results.status);
+    let proof = results.proof.expect("Proof of closed ballot is absent");
+
+    let to_tally = proof.to_tally.check().unwrap();
+    let tally = BallotSchema::new(testkit.snapshot())
+        .tallies()
+        .get(&proposals_hash)
+        .unwrap();
+    assert_eq!(vec![(&proposals_hash, &tally)], to_tally.entries());
+
+    let to_table = proof.to_table.check().unwrap();
+    assert_eq!(&to_tally.merkle_root(), to_table.entries()[0].1);
+    assert_eq!(to_table.merkle_root(), *proof.block_info.block.state_hash());
+    assert!(results.finalization.is_none());
+
+    client_with_keys(&testkit, &validators[0])
+        .finalize(&proposals_hash)
+        .unwrap();
+    testkit.create_block();
+    let finalization = client
+        .results(&proposals_hash)
+        .unwrap()
+        .unwrap()
+        .finalization
+        .expect("Ballot isn't finalized");
+    assert_eq!(Outcome::Winner { proposal_id: 2 }, finalization.outcome);
+    assert!(finalization.quorum_reached);
+    assert_eq!(Some(3), finalization.quorum);
+    assert_eq!(None, finalization.action);
+    assert_eq!(4, client.voters().unwrap().len());
+
+    // A later quorum change leaves the results of finalized ballots as they were.
+    let config = BallotConfig {
+        quorum: Threshold {
+            numerator: 9,
+            denominator: 10,
+        },
+        ..Default::default()
+    };
+    let resp = client_with_keys(&testkit, &validators[0])
+        .propose_config(&config)
+        .unwrap();
+    testkit.create_block();
+    for validator in &validators[1..3] {
+        client_with_keys(&testkit, validator)
+            .vote_config(&resp.config_hash)
+            .unwrap();
+    }
+    testkit.create_block();
+    assert_eq!(config, client.config().unwrap());
+    let results = client.results(&proposals_hash).unwrap().unwrap();
+    assert_eq!(3, results.quorum);
+    assert!(results.quorum_reached);
+
+    assert!(client.results(&Hash::zero()).unwrap().is_none());
+}
+
+#[test]
+fn test_voter_history() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let proposals_list = vec![
+        r#"{"id": 1, "deadline": 5, "proposals": [{"id": 1, "subject": "triss", "description": "magic"}]}"#,
+        r#"{"id": 2, "deadline": 100, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}]}"#,
+    ];
+    let proposals_list = proposals_list
+        .iter()
+        .map(|json| ProposalList::try_deserialize(json.as_bytes()).unwrap())
+        .collect::<Vec<ProposalList>>();
+    let validators = testkit.network().validators().to_vec();
+    let tx_ballot_list = proposals_list
+        .iter()
+        .map(|proposals| new_tx_ballot(&validators[0], proposals.clone()))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_ballot_list);
+
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "triss".to_string(),
+    };
+    let tx_vote = new_tx_vote(&validators[1], &proposals_list[0].hash(), &vote_req);
+    testkit.create_block_with_transaction(tx_vote.clone());
+    let vote_height = testkit.height().0;
+    testkit.create_blocks_until(Height(10));
+
+    let client = client(&testkit);
+    let history = client
+        .voter_history(validators[1].service_keypair().0)
+        .unwrap();
+    assert_eq!(1, history.votes.len());
+    assert_eq!(tx_vote, history.votes[0].tx_vote());
+    assert_eq!(vote_height, history.votes[0].height());
+    assert_eq!(
+        ParticipationStats {
+            votes_cast: 1,
+            ballots_total: 2,
+            ballots_closed: 1,
+            closed_voted: 1,
+            closed_missed: 0,
+            participation_rate: Some(1.0),
+        },
+        history.stats
+    );
+
+    let history = client
+        .voter_history(validators[2].service_keypair().0)
+        .unwrap();
+    assert!(history.votes.is_empty());
+    assert_eq!(1, history.stats.closed_missed);
+    assert_eq!(Some(0.0), history.stats.participation_rate);
+
+    // Keys outside the electorate miss nothing.
+    let history = client.voter_history(&crypto::gen_keypair().0).unwrap();
+    assert_eq!(0, history.stats.ballots_closed);
+    assert_eq!(0, history.stats.closed_missed);
+    assert_eq!(None, history.stats.participation_rate);
+}
+
 #[test]
 fn test_post_ballot() {
     let mut testkit: TestKit = TestKit::ballot_default();
@@ -200,3 +653,227 @@ fn test_post_vote() {
     assert_eq!(tx.hash(), resp.tx_hash);
     assert!(testkit.is_tx_in_pool(&resp.tx_hash));
 }
+
+#[test]
+fn test_post_signed_ballot_json() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (_, proposals) = new_proposals_data();
+
+    let tx = new_tx_ballot(&testkit.network().validators()[1], proposals);
+    let signed_tx = SignedTransaction::Json(BallotTransactions::Ballot(tx.clone()));
+    let resp = client(&testkit).submit(&signed_tx).unwrap();
+    testkit.poll_events();
+
+    assert_eq!(tx.hash(), resp.tx_hash);
+    assert!(testkit.is_tx_in_pool(&resp.tx_hash));
+}
+
+#[test]
+fn test_post_signed_vote_hex() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (_, proposals) = create_test_ballot!(testkit);
+
+    let vote_req = VoteRequest {
+        proposal_id: 2,
+        proposal_subject: "ciri".to_string(),
+    };
+    let tx = new_tx_vote(
+        &testkit.network().validators()[2],
+        &proposals.hash(),
+        &vote_req,
+    );
+    let tx_bytes: &[u8] = tx.raw().as_ref();
+    let signed_tx = SignedTransaction::Hex {
+        tx_hex: encode_hex(tx_bytes),
+    };
+    let resp = client(&testkit).submit(&signed_tx).unwrap();
+    testkit.poll_events();
+
+    assert_eq!(tx.hash(), resp.tx_hash);
+    assert!(testkit.is_tx_in_pool(&resp.tx_hash));
+}
+
+fn assert_rejected(testkit: &mut TestKit, tx_bytes: &[u8]) {
+    let signed_tx = SignedTransaction::Hex {
+        tx_hex: encode_hex(tx_bytes),
+    };
+    match client(testkit).submit(&signed_tx) {
+        Err(ClientError::Status(400, _)) => {}
+        other => panic!("Expected bad request, got {:?}", other),
+    }
+    testkit.poll_events();
+    assert!(!testkit.is_tx_in_pool(&crypto::hash(tx_bytes)));
+}
+
+#[test]
+fn test_post_signed_tx_with_bad_signature() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (_, proposals) = new_proposals_data();
+
+    let tx = new_tx_ballot(&testkit.network().validators()[1], proposals);
+    let mut tx_bytes = tx.raw().as_ref().to_vec();
+    *tx_bytes.last_mut().unwrap() ^= 1;
+
+    assert_rejected(&mut testkit, &tx_bytes);
+}
+
+#[test]
+fn test_post_signed_tx_with_wrong_service_id() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (_, proposals) = new_proposals_data();
+
+    let tx = new_tx_ballot(&testkit.network().validators()[1], proposals);
+    let mut tx_bytes = tx.raw().as_ref().to_vec();
+    // Service id is the little endian u16 at offset 4 of the message header.
+    let service_id = SERVICE_ID + 1;
+    tx_bytes[4] = service_id as u8;
+    tx_bytes[5] = (service_id >> 8) as u8;
+
+    assert_rejected(&mut testkit, &tx_bytes);
+}
+
+#[test]
+fn test_post_signed_tx_truncated() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (_, proposals) = create_test_ballot!(testkit);
+
+    let vote_req = VoteRequest {
+        proposal_id: 2,
+        proposal_subject: "ciri".to_string(),
+    };
+    let tx = new_tx_vote(
+        &testkit.network().validators()[2],
+        &proposals.hash(),
+        &vote_req,
+    );
+    let tx_bytes: &[u8] = tx.raw().as_ref();
+
+    assert_rejected(&mut testkit, &tx_bytes[..tx_bytes.len() - 1]);
+    assert_rejected(&mut testkit, &tx_bytes[..8]);
+
+    // Odd number of hex digits.
+    let mut tx_hex = encode_hex(tx_bytes);
+    tx_hex.pop();
+    let signed_tx = SignedTransaction::Hex { tx_hex };
+    match client(&testkit).submit(&signed_tx) {
+        Err(ClientError::Status(400, _)) => {}
+        other => panic!("Expected bad request, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_client_signs_ballot_and_vote() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validator = testkit.network().validators()[3].clone();
+    let client = client_with_keys(&testkit, &validator);
+    let (_, proposals) = new_proposals_data();
+
+    let resp = client.create_ballot(&proposals).unwrap();
+    assert_eq!(proposals.hash(), resp.proposals_hash);
+    testkit.create_block();
+    assert!(client.ballot(&resp.proposals_hash).unwrap().ballot.is_some());
+
+    let vote_req = VoteRequest {
+        proposal_id: 3,
+        proposal_subject: "yennefer".to_string(),
+    };
+    let resp = client.vote(&proposals.hash(), &vote_req).unwrap();
+    testkit.create_block();
+
+    let tx = new_tx_vote(&validator, &proposals.hash(), &vote_req);
+    assert_eq!(tx.hash(), resp.tx_hash);
+    assert!(testkit.votes(&proposals.hash()).contains(&Some(tx)));
+}
+
+#[test]
+fn test_client_changes_config() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+    assert_eq!(BallotConfig::default(), client(&testkit).config().unwrap());
+
+    let config = BallotConfig {
+        quorum: Threshold {
+            numerator: 1,
+            denominator: 2,
+        },
+        ..Default::default()
+    };
+    let resp = client_with_keys(&testkit, &validators[0])
+        .propose_config(&config)
+        .unwrap();
+    assert_eq!(config.hash(), resp.config_hash);
+    testkit.create_block();
+
+    let proposal = client(&testkit)
+        .config_proposal(&resp.config_hash)
+        .unwrap()
+        .unwrap();
+    assert_eq!(config, proposal.config);
+    assert_eq!(vec![*validators[0].service_keypair().0], proposal.approvals);
+
+    for validator in &validators[1..3] {
+        client_with_keys(&testkit, validator)
+            .vote_config(&resp.config_hash)
+            .unwrap();
+    }
+    testkit.create_block();
+    assert_eq!(config, client(&testkit).config().unwrap());
+}
+
+#[test]
+fn test_transaction_status() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validator = testkit.network().validators()[1].clone();
+    let client = client_with_keys(&testkit, &validator);
+    let (_, proposals) = new_proposals_data();
+
+    let resp = client.create_ballot(&proposals).unwrap();
+    assert_eq!(
+        TransactionStatus::Unknown,
+        client.transaction_status(&resp.tx_hash, 0).unwrap()
+    );
+    testkit.poll_events();
+    assert_eq!(
+        TransactionStatus::Pending,
+        client.transaction_status(&resp.tx_hash, 0).unwrap()
+    );
+    testkit.create_block();
+    assert_eq!(
+        TransactionStatus::Committed { height: 1 },
+        client.transaction_status(&resp.tx_hash, 0).unwrap()
+    );
+
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "triss".to_string(),
+    };
+    let tx_vote = new_tx_vote(&validator, &proposals.hash(), &vote_req);
+    testkit.create_block_with_transaction(tx_vote);
+    let vote_req = VoteRequest {
+        proposal_id: 2,
+        proposal_subject: "ciri".to_string(),
+    };
+    let tx_illegal_vote = new_tx_vote(&validator, &proposals.hash(), &vote_req);
+    testkit.create_block_with_transaction(tx_illegal_vote.clone());
+
+    match client.transaction_status(&tx_illegal_vote.hash(), 0).unwrap() {
+        TransactionStatus::Failed {
+            height,
+            code,
+            error_code,
+            description,
+            details,
+            ..
+        } => {
+            assert_eq!(3, height);
+            assert_eq!(Some(ErrorCode::AlreadyVoted as u8), code);
+            assert_eq!(Some(ErrorCode::AlreadyVoted), error_code);
+            let expected = ServiceError::AlreadyVoted {
+                voter: *validator.service_keypair().0,
+            };
+            assert_eq!(Some(expected.to_string()), description);
+            assert_eq!(Some(expected), details);
+        }
+        status => panic!("Unexpected transaction status: {:?}", status),
+    }
+}
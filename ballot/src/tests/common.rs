@@ -1,9 +1,10 @@
 use exonum::crypto::Hash;
-use exonum::storage::StorageValue;
+use exonum::storage::{Database, MemoryDB, ProofListIndex, Snapshot, StorageValue};
 use exonum_testkit::TestNode;
 
-use schema::ProposalList;
-use transactions::{Ballot, Vote};
+use schema::{Amendment, Answer, MaybeVote, ProposalAction, ProposalList, Schema as BallotSchema};
+use transactions::{AmendBallot, Ballot, FinalizeBallot, Nominate, SponsorBallot, Vote,
+                   VoteQuestions};
 use api::VoteRequest;
 
 macro_rules! create_test_ballot {
@@ -27,6 +28,19 @@ pub fn new_proposals_data() -> (String, ProposalList) {
     (proposals_str.to_string(), proposals)
 }
 
+/// Proposals where `yes` carries `action` and `no` keeps things as they are.
+pub fn new_action_proposals(deadline: u64, action: &ProposalAction) -> ProposalList {
+    let proposals_str = format!(
+        r#"{{"id": 1, "deadline": {}, "proposals":[
+                    {{"id": 1, "subject": "yes", "description": "apply", "action": {}}}
+                  , {{"id": 2, "subject": "no", "description": "keep"}}
+                  ]}}"#,
+        deadline,
+        ::serde_json::to_string(action).unwrap()
+    );
+    ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap()
+}
+
 pub fn new_tx_ballot(node: &TestNode, proposals: ProposalList) -> Ballot {
     let keypair = node.service_keypair();
     Ballot::new(
@@ -46,3 +60,71 @@ pub fn new_tx_vote(node: &TestNode, proposals_hash: &Hash, vote_req: &VoteReques
         keypair.1,
     )
 }
+
+pub fn new_tx_finalize(node: &TestNode, proposals_hash: &Hash) -> FinalizeBallot {
+    let keypair = node.service_keypair();
+    FinalizeBallot::new(keypair.0, proposals_hash, keypair.1)
+}
+
+pub fn new_tx_sponsor(node: &TestNode, proposals_hash: &Hash) -> SponsorBallot {
+    let keypair = node.service_keypair();
+    SponsorBallot::new(keypair.0, proposals_hash, keypair.1)
+}
+
+pub fn new_tx_amend(node: &TestNode, proposals_hash: &Hash, amendment: &str) -> AmendBallot {
+    let keypair = node.service_keypair();
+    // Round trip to catch malformed amendments in the test itself.
+    let amendment = Amendment::try_deserialize(amendment.as_bytes()).unwrap();
+    AmendBallot::new(
+        keypair.0,
+        proposals_hash,
+        &::serde_json::to_string(&amendment).unwrap(),
+        keypair.1,
+    )
+}
+
+pub fn new_tx_nominate(node: &TestNode, proposals_hash: &Hash, subject: &str) -> Nominate {
+    let keypair = node.service_keypair();
+    Nominate::new(keypair.0, proposals_hash, subject, "write-in", keypair.1)
+}
+
+pub fn new_tx_answers(node: &TestNode, proposals_hash: &Hash, answers: &str) -> VoteQuestions {
+    let keypair = node.service_keypair();
+    // Round trip to catch malformed answers in the test itself.
+    let answers = Answer::try_deserialize_list(answers.as_bytes()).unwrap();
+    VoteQuestions::new(
+        keypair.0,
+        proposals_hash,
+        &::serde_json::to_string(&answers).unwrap(),
+        keypair.1,
+    )
+}
+
+/// Recomputes every ballot's `votes_history_hash` from its stored vote list.
+pub fn assert_votes_history_consistent(snapshot: &Snapshot) {
+    let schema = BallotSchema::new(snapshot);
+
+    for proposals_hash in schema.proposals_hash_by_ordinal().iter() {
+        let ballot_data = schema
+            .ballot_data_by_proposals_hash()
+            .get(&proposals_hash)
+            .expect("Data for ballot is absent");
+
+        let db = MemoryDB::new();
+        let mut fork = db.fork();
+        let votes_history_hash = {
+            let mut votes = ProofListIndex::new("votes", &mut fork);
+            for vote in schema.votes(&proposals_hash) {
+                votes.push(MaybeVote::from(vote));
+            }
+            votes.merkle_root()
+        };
+
+        assert_eq!(
+            &votes_history_hash,
+            ballot_data.votes_history_hash(),
+            "Votes history hash mismatch for ballot: {:?}",
+            proposals_hash
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::mpsc::Receiver;
+
+use exonum::crypto::{CryptoHash, Hash};
+use exonum::helpers::Height;
+use exonum_testkit::{TestKit, TestKitBuilder};
+
+use BallotService;
+use error::ErrorCode;
+use events::BallotEvent;
+use api::VoteRequest;
+use tests::common::*;
+use tests::tx_logic::BallotTestKit;
+
+fn ballot_with_events() -> (TestKit, Receiver<BallotEvent>) {
+    let service = BallotService::new();
+    let events = service.subscribe();
+    let testkit = TestKitBuilder::validator()
+        .with_validators(4)
+        .with_service(service)
+        .create();
+    (testkit, events)
+}
+
+fn events_for(events: &Receiver<BallotEvent>, proposals_hash: &Hash) -> Vec<BallotEvent> {
+    events
+        .try_iter()
+        .filter(|event| event.proposals_hash() == Some(proposals_hash))
+        .collect()
+}
+
+#[test]
+fn test_ballot_and_vote_events() {
+    let (mut testkit, events) = ballot_with_events();
+
+    let (_, proposals) = new_proposals_data();
+    let proposals_hash = proposals.hash();
+    let validator = testkit.network().validators()[1].clone();
+    let tx_ballot = new_tx_ballot(&validator, proposals);
+    testkit.create_block_with_transaction(tx_ballot.clone());
+
+    assert_eq!(
+        vec![
+            BallotEvent::BallotCreated {
+                proposals_hash,
+                tx_hash: tx_ballot.hash(),
+                from: *tx_ballot.from(),
+                height: 1,
+            },
+        ],
+        events_for(&events, &proposals_hash)
+    );
+
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "triss".to_string(),
+    };
+    let tx_vote = new_tx_vote(&validator, &proposals_hash, &vote_req);
+    let vote_req = VoteRequest {
+        proposal_id: 2,
+        proposal_subject: "ciri".to_string(),
+    };
+    let tx_illegal_vote = new_tx_vote(&validator, &proposals_hash, &vote_req);
+    testkit.create_block_with_transactions(txvec![tx_vote.clone(), tx_illegal_vote.clone()]);
+
+    let block_events = events_for(&events, &proposals_hash);
+    assert_eq!(2, block_events.len());
+    assert!(block_events.contains(&BallotEvent::VoteCast {
+        proposals_hash,
+        tx_hash: tx_vote.hash(),
+        from: *tx_vote.from(),
+        proposal_id: 1,
+        height: 2,
+    }));
+    assert!(block_events.iter().any(|event| match *event {
+        BallotEvent::TxRejected {
+            ref tx_hash,
+            error_code,
+            ..
+        } => *tx_hash == tx_illegal_vote.hash() && error_code == Some(ErrorCode::AlreadyVoted),
+        _ => false,
+    }));
+}
+
+#[test]
+fn test_ballot_closed_event() {
+    let (mut testkit, events) = ballot_with_events();
+
+    let (_, proposals) = create_test_ballot!(testkit);
+    let proposals_hash = proposals.hash();
+    let deadline = proposals.deadline();
+
+    testkit.create_blocks_until(Height(deadline));
+    let closed = BallotEvent::BallotClosed {
+        proposals_hash,
+        deadline,
+    };
+    assert!(!events_for(&events, &proposals_hash).contains(&closed));
+
+    testkit.create_block();
+    assert_eq!(vec![closed], events_for(&events, &proposals_hash));
+}
+
+#[test]
+fn test_event_streams_are_capped() {
+    use events::{EventHub, MAX_EVENT_STREAMS};
+
+    let hub = EventHub::new();
+    let streams = (0..MAX_EVENT_STREAMS)
+        .map(|_| hub.open_stream(None).unwrap())
+        .collect::<Vec<_>>();
+    assert!(hub.open_stream(None).is_none());
+
+    drop(streams);
+    assert!(hub.open_stream(None).is_some());
+}
+
+#[test]
+fn test_lagging_subscribers_are_dropped() {
+    use std::sync::mpsc::TryRecvError;
+    use exonum::crypto;
+    use events::{EventHub, SUBSCRIBER_BUFFER};
+
+    let hub = EventHub::new();
+    let events = hub.subscribe();
+    let event = BallotEvent::BallotCreated {
+        proposals_hash: Hash::zero(),
+        tx_hash: Hash::zero(),
+        from: crypto::gen_keypair().0,
+        height: 1,
+    };
+    hub.publish(vec![event; SUBSCRIBER_BUFFER + 1]);
+
+    assert_eq!(SUBSCRIBER_BUFFER, events.try_iter().count());
+    assert_eq!(Err(TryRecvError::Disconnected), events.try_recv());
+}
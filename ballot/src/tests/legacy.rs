@@ -0,0 +1,269 @@
+//! The service as released before the storage layout was versioned, kept to write the
+//! databases the migration tests upgrade. Its transactions share the wire format of
+//! `Ballot` and `Vote`, only their execution is frozen at the old behavior.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use exonum::blockchain::{ApiContext, ExecutionError, ExecutionResult, Schema as CoreSchema,
+                         Service, ServiceContext, Transaction, TransactionSet};
+use exonum::crypto::{CryptoHash, Hash, PublicKey};
+use exonum::encoding;
+use exonum::helpers::Height;
+use exonum::messages::{Message, RawTransaction};
+use exonum::storage::{Fork, ProofListIndex, ProofMapIndex, Snapshot};
+use exonum_testkit::{TestKit, TestKitBuilder};
+use iron::Handler;
+use serde_json::Value;
+
+use {BallotService, SERVICE_ID, SERVICE_NAME};
+use schema::{MaybeVote, ProposalList};
+use transactions::{Ballot, Vote};
+
+const BALLOTS: &str = "ballot.ballots";
+const PROPOSALS_HASHES: &str = "ballot.proposals_hashes";
+const VOTES: &str = "ballot.votes";
+
+encoding_struct! {
+    /// `BallotData` before `num_voters` was split, which `Vote` overwrote with the
+    /// voter's validator id.
+    struct LegacyBallotData {
+        tx_ballot: Ballot,
+        votes_history_hash: &Hash,
+        num_voters: u64,
+    }
+}
+
+transactions! {
+    LegacyTransactions {
+        const SERVICE_ID = SERVICE_ID;
+
+        struct LegacyBallot {
+            from: &PublicKey,
+            proposals: &str,
+        }
+
+        struct LegacyVote {
+            from: &PublicKey,
+            proposals_hash: &Hash,
+            proposal_id: u64,
+            proposal_subject: &str,
+        }
+    }
+}
+
+impl From<Ballot> for LegacyBallot {
+    fn from(tx: Ballot) -> LegacyBallot {
+        LegacyBallot::from_raw(tx.raw().clone()).unwrap()
+    }
+}
+
+impl From<Vote> for LegacyVote {
+    fn from(tx: Vote) -> LegacyVote {
+        LegacyVote::from_raw(tx.raw().clone()).unwrap()
+    }
+}
+
+fn validator_id(snapshot: &Snapshot, key: &PublicKey) -> Option<usize> {
+    let actual_config = CoreSchema::new(snapshot).actual_configuration();
+    actual_config
+        .validator_keys
+        .iter()
+        .position(|k| k.service_key == *key)
+}
+
+fn rejected(description: &str) -> ExecutionResult {
+    Err(ExecutionError::with_description(0, description))
+}
+
+fn ballots(view: &mut Fork) -> ProofMapIndex<&mut Fork, Hash, LegacyBallotData> {
+    ProofMapIndex::new(BALLOTS, view)
+}
+
+fn votes<'a>(
+    view: &'a mut Fork,
+    proposals_hash: &Hash,
+) -> ProofListIndex<&'a mut Fork, MaybeVote> {
+    ProofListIndex::new_in_family(VOTES, proposals_hash, view)
+}
+
+impl Transaction for LegacyBallot {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        if validator_id(view.as_ref(), self.from()).is_none() {
+            return rejected("unknown sender");
+        }
+        let proposals = match ProposalList::try_deserialize(self.proposals().as_bytes()) {
+            Ok(proposals) => proposals,
+            Err(_) => return rejected("invalid proposals"),
+        };
+        let proposals_hash = proposals.hash();
+        if ballots(view).contains(&proposals_hash) {
+            return rejected("ballot already posted");
+        }
+
+        let num_validators = CoreSchema::new(view.as_ref())
+            .actual_configuration()
+            .validator_keys
+            .len();
+        let votes_history_hash = {
+            let mut votes_table = votes(view, &proposals_hash);
+            for _ in 0..num_validators {
+                votes_table.push(MaybeVote::none());
+            }
+            votes_table.merkle_root()
+        };
+
+        let tx_ballot = Ballot::from_raw(self.raw().clone()).unwrap();
+        ballots(view).put(
+            &proposals_hash,
+            LegacyBallotData::new(tx_ballot, &votes_history_hash, num_validators as u64),
+        );
+        ProofListIndex::new(PROPOSALS_HASHES, view).push(proposals_hash);
+        Ok(())
+    }
+}
+
+impl Transaction for LegacyVote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let validator_id = match validator_id(view.as_ref(), self.from()) {
+            Some(validator_id) => validator_id as u64,
+            None => return rejected("unknown sender"),
+        };
+        let ballot_data = match ballots(view).get(self.proposals_hash()) {
+            Some(ballot_data) => ballot_data,
+            None => return rejected("ballot doesn't exist"),
+        };
+        match votes(view, self.proposals_hash()).get(validator_id) {
+            Some(ref vote) if vote.is_none() => {}
+            _ => return rejected("already voted"),
+        }
+        let proposals = ProposalList::try_deserialize(
+            ballot_data.tx_ballot().proposals().as_bytes(),
+        ).unwrap();
+        if !proposals.contains(self.proposal_id(), self.proposal_subject()) {
+            return rejected("proposal doesn't exist");
+        }
+        if CoreSchema::new(view.as_ref()).height() > Height(proposals.deadline()) {
+            return rejected("ballot closed");
+        }
+
+        let votes_history_hash = {
+            let tx_vote = Vote::from_raw(self.raw().clone()).unwrap();
+            let mut votes_table = votes(view, self.proposals_hash());
+            votes_table.set(validator_id, MaybeVote::some(tx_vote));
+            votes_table.merkle_root()
+        };
+        ballots(view).put(
+            self.proposals_hash(),
+            LegacyBallotData::new(ballot_data.tx_ballot(), &votes_history_hash, validator_id),
+        );
+        Ok(())
+    }
+}
+
+/// Runs as the legacy service until `upgrade` is called, then as `BallotService`,
+/// the way a node restarted with the new binary does.
+struct LegacyService {
+    upgraded: Arc<AtomicBool>,
+    current: BallotService,
+}
+
+impl Service for LegacyService {
+    fn service_name(&self) -> &'static str {
+        SERVICE_NAME
+    }
+
+    fn service_id(&self) -> u16 {
+        SERVICE_ID
+    }
+
+    fn tx_from_raw(&self, raw: RawTransaction) -> Result<Box<Transaction>, encoding::Error> {
+        if self.upgraded.load(Ordering::SeqCst) {
+            self.current.tx_from_raw(raw)
+        } else {
+            Ok(LegacyTransactions::tx_from_raw(raw)?.into())
+        }
+    }
+
+    /// The legacy service stored nothing at genesis.
+    fn initialize(&self, _fork: &mut Fork) -> Value {
+        Value::Null
+    }
+
+    fn state_hash(&self, snapshot: &Snapshot) -> Vec<Hash> {
+        if self.upgraded.load(Ordering::SeqCst) {
+            self.current.state_hash(snapshot)
+        } else {
+            vec![
+                ProofMapIndex::<_, Hash, LegacyBallotData>::new(BALLOTS, snapshot).merkle_root(),
+                ProofListIndex::<_, Hash>::new(PROPOSALS_HASHES, snapshot).merkle_root(),
+            ]
+        }
+    }
+
+    fn handle_commit(&self, context: &ServiceContext) {
+        if self.upgraded.load(Ordering::SeqCst) {
+            self.current.handle_commit(context);
+        }
+    }
+
+    /// Handlers are created once, they are only used after the upgrade.
+    fn public_api_handler(&self, context: &ApiContext) -> Option<Box<Handler>> {
+        self.current.public_api_handler(context)
+    }
+
+    fn private_api_handler(&self, context: &ApiContext) -> Option<Box<Handler>> {
+        self.current.private_api_handler(context)
+    }
+}
+
+/// Network of 4 validators running the legacy service.
+pub struct LegacyNetwork {
+    pub testkit: TestKit,
+    upgraded: Arc<AtomicBool>,
+}
+
+impl LegacyNetwork {
+    pub fn new() -> LegacyNetwork {
+        let upgraded = Arc::new(AtomicBool::new(false));
+        let service = LegacyService {
+            upgraded: Arc::clone(&upgraded),
+            current: BallotService::new(),
+        };
+        let testkit = TestKitBuilder::validator()
+            .with_validators(4)
+            .with_service(service)
+            .create();
+        LegacyNetwork { testkit, upgraded }
+    }
+
+    pub fn post_ballot(&mut self, tx_ballot: Ballot) {
+        self.testkit
+            .create_block_with_transaction(LegacyBallot::from(tx_ballot));
+    }
+
+    pub fn vote(&mut self, tx_vote: Vote) {
+        self.testkit
+            .create_block_with_transaction(LegacyVote::from(tx_vote));
+    }
+
+    pub fn ballot_data(&self, proposals_hash: &Hash) -> Option<LegacyBallotData> {
+        let snapshot = self.testkit.snapshot();
+        ProofMapIndex::<_, Hash, LegacyBallotData>::new(BALLOTS, &snapshot).get(proposals_hash)
+    }
+
+    /// Switches the nodes to `BallotService`, transactions from here on run the
+    /// current code. The database is left as the legacy service wrote it.
+    pub fn upgrade(self) -> TestKit {
+        self.upgraded.store(true, Ordering::SeqCst);
+        self.testkit
+    }
+}
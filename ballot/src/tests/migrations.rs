@@ -0,0 +1,209 @@
+use exonum::crypto::{CryptoHash, Hash};
+use exonum::storage::{Entry, Fork};
+use exonum_testkit::TestKit;
+
+use migrations::{migrate, MigrationError, SCHEMA_VERSION};
+use schema::{BallotConfig, Schema as BallotSchema, Tally};
+use transactions::Ballot;
+use api::VoteRequest;
+use client::BallotClient;
+use tests::common::*;
+use tests::legacy::LegacyNetwork;
+use tests::tx_logic::BallotTestKit;
+
+/// Applies `change` to the testkit database outside of transactions.
+fn rewrite<F: FnOnce(&mut Fork)>(testkit: &mut TestKit, change: F) {
+    let blockchain = testkit.blockchain_mut();
+    let mut fork = blockchain.fork();
+    change(&mut fork);
+    blockchain.merge(fork.into_patch()).unwrap();
+}
+
+fn vote_request(proposal_id: u64, subject: &str) -> VoteRequest {
+    VoteRequest {
+        proposal_id,
+        proposal_subject: subject.to_string(),
+    }
+}
+
+/// Database with a voted ballot, as written by the current code.
+fn voted_ballot() -> (TestKit, Ballot, Hash) {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (tx_ballot, proposals) = create_test_ballot!(testkit);
+    let proposals_hash = proposals.hash();
+
+    let tx_vote = new_tx_vote(
+        &testkit.network().validators()[2],
+        &proposals_hash,
+        &vote_request(1, "triss"),
+    );
+    testkit.create_block_with_transaction(tx_vote);
+    (testkit, tx_ballot, proposals_hash)
+}
+
+/// Version 0 database written by the legacy service: a ballot of validator 0 voted by
+/// validator 2, with the nodes switched to the current code but not yet migrated.
+fn version_0_database() -> (TestKit, Hash) {
+    let mut network = LegacyNetwork::new();
+    let (_, proposals) = new_proposals_data();
+    let proposals_hash = proposals.hash();
+
+    let tx_ballot = new_tx_ballot(&network.testkit.network().validators()[0], proposals);
+    network.post_ballot(tx_ballot);
+    let tx_vote = new_tx_vote(
+        &network.testkit.network().validators()[2],
+        &proposals_hash,
+        &vote_request(1, "triss"),
+    );
+    network.vote(tx_vote);
+    // The legacy `Vote` overwrote `num_voters` with the voter's validator id.
+    assert_eq!(2, network.ballot_data(&proposals_hash).unwrap().num_voters());
+
+    (network.upgrade(), proposals_hash)
+}
+
+#[test]
+fn test_genesis_is_current_version() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    create_test_ballot!(testkit);
+    assert_eq!(
+        Some(SCHEMA_VERSION),
+        BallotSchema::new(testkit.snapshot()).schema_version().get()
+    );
+
+    let state_hash = BallotSchema::new(testkit.snapshot()).state_hash();
+    assert_eq!(SCHEMA_VERSION, migrate(testkit.blockchain_mut()).unwrap());
+    assert_eq!(state_hash, BallotSchema::new(testkit.snapshot()).state_hash());
+}
+
+#[test]
+fn test_migrate_version_0_database() {
+    let (mut testkit, proposals_hash) = version_0_database();
+    let state_hash = BallotSchema::new(testkit.snapshot()).state_hash();
+    assert_eq!(None, BallotSchema::new(testkit.snapshot()).schema_version().get());
+
+    assert_eq!(0, migrate(testkit.blockchain_mut()).unwrap());
+
+    let snapshot = testkit.snapshot();
+    let schema = BallotSchema::new(&snapshot);
+    assert_eq!(Some(SCHEMA_VERSION), schema.schema_version().get());
+    assert_eq!(state_hash, schema.state_hash());
+    assert_votes_history_consistent(&snapshot);
+
+    let ballot = schema.ballot(&proposals_hash).unwrap();
+    let deadline = schema.proposals(&proposals_hash).unwrap().deadline();
+    assert_eq!(vec![deadline], schema.deadlines().iter().collect::<Vec<_>>());
+    assert_eq!(vec![0], schema.ordinals_by_deadline(deadline).iter().collect::<Vec<_>>());
+    assert_eq!(vec![0], schema.ordinals_by_author(ballot.from()).iter().collect::<Vec<_>>());
+
+    let voter = *testkit.network().validators()[2].service_keypair().0;
+    let records = schema.votes_by_voter(&voter).iter().collect::<Vec<_>>();
+    assert_eq!(1, records.len());
+    assert_eq!(&proposals_hash, records[0].tx_vote().proposals_hash());
+    assert_eq!(2, records[0].height());
+
+    // Electorates of ballots posted before they were stored come from the validators.
+    assert_eq!(4, schema.electorate(&proposals_hash).unwrap().len());
+    assert_eq!(BallotConfig::default(), schema.config());
+}
+
+#[test]
+fn test_recount_participation_of_legacy_ballots() {
+    let (mut testkit, proposals_hash) = version_0_database();
+    migrate(testkit.blockchain_mut()).unwrap();
+
+    let tx_vote = new_tx_vote(
+        &testkit.network().validators()[0],
+        &proposals_hash,
+        &vote_request(2, "ciri"),
+    );
+    testkit.create_block_with_transaction(tx_vote);
+
+    testkit.assert_participation(&proposals_hash, 4, 2);
+    let snapshot = testkit.snapshot();
+    assert_votes_history_consistent(&snapshot);
+    let schema = BallotSchema::new(&snapshot);
+    let proposals = schema.proposals(&proposals_hash).unwrap();
+    let tally = schema.tallies().get(&proposals_hash).unwrap();
+    assert_eq!(Tally::from_votes(&proposals, &schema.votes(&proposals_hash)), tally);
+    assert_eq!(1, tally.votes(1));
+    assert_eq!(1, tally.votes(2));
+}
+
+#[test]
+fn test_recount_participation_of_closed_legacy_ballots() {
+    use exonum::helpers::Height;
+
+    let (mut testkit, proposals_hash) = version_0_database();
+    migrate(testkit.blockchain_mut()).unwrap();
+    let deadline = BallotSchema::new(testkit.snapshot())
+        .proposals(&proposals_hash)
+        .unwrap()
+        .deadline();
+    testkit.create_blocks_until(Height(deadline + 1));
+
+    let tx_finalize = new_tx_finalize(&testkit.network().validators()[1], &proposals_hash);
+    testkit.create_block_with_transaction(tx_finalize);
+
+    testkit.assert_participation(&proposals_hash, 4, 1);
+    let snapshot = testkit.snapshot();
+    let schema = BallotSchema::new(&snapshot);
+    assert!(schema.finalizations().get(&proposals_hash).is_some());
+    assert_eq!(
+        schema.votes_by_proposals_hash(&proposals_hash).merkle_root(),
+        *schema
+            .ballot_data_by_proposals_hash()
+            .get(&proposals_hash)
+            .unwrap()
+            .votes_history_hash()
+    );
+}
+
+#[test]
+fn test_results_of_legacy_ballots() {
+    let (mut testkit, proposals_hash) = version_0_database();
+    migrate(testkit.blockchain_mut()).unwrap();
+
+    let results = BallotClient::new(testkit.api())
+        .results(&proposals_hash)
+        .unwrap()
+        .unwrap();
+    let validators = testkit.network().validators();
+    let non_voters = [0, 1, 3]
+        .iter()
+        .map(|&i| *validators[i].service_keypair().0)
+        .collect::<Vec<_>>();
+    assert_eq!(non_voters, results.non_voters);
+    assert_eq!(1, results.votes_cast);
+}
+
+#[test]
+fn test_migrate_current_ballots_is_noop() {
+    let (mut testkit, tx_ballot, _) = voted_ballot();
+    let state_hash = BallotSchema::new(testkit.snapshot()).state_hash();
+
+    assert_eq!(SCHEMA_VERSION, migrate(testkit.blockchain_mut()).unwrap());
+
+    let snapshot = testkit.snapshot();
+    let schema = BallotSchema::new(&snapshot);
+    assert_eq!(state_hash, schema.state_hash());
+    assert_eq!(1, schema.ordinals_by_author(tx_ballot.from()).len());
+    let voter = *testkit.network().validators()[2].service_keypair().0;
+    assert_eq!(1, schema.votes_by_voter(&voter).len());
+}
+
+#[test]
+fn test_migrate_newer_database() {
+    let (mut testkit, _, _) = voted_ballot();
+    rewrite(&mut testkit, |fork| {
+        let mut version: Entry<_, u32> = Entry::new("ballot.schema_version", fork);
+        version.set(SCHEMA_VERSION + 1);
+    });
+
+    match migrate(testkit.blockchain_mut()) {
+        Err(MigrationError::UnsupportedVersion(version)) => {
+            assert_eq!(SCHEMA_VERSION + 1, version)
+        }
+        other => panic!("Unexpected migration result: {:?}", other),
+    }
+}
@@ -2,3 +2,6 @@
 mod common;
 mod tx_logic;
 mod api;
+mod events;
+mod legacy;
+mod migrations;
@@ -1,10 +1,15 @@
 use exonum::crypto::{self, hash, CryptoHash, Hash};
-use exonum_testkit::{TestKit, TestKitBuilder};
+use exonum::blockchain::{ExecutionError, Schema as CoreSchema, Transaction, ValidatorKeys};
+#[cfg(feature = "validator-set")]
+use exonum::blockchain::StoredConfiguration;
+use exonum_testkit::{TestKit, TestKitBuilder, TestNode};
 
-use BallotService;
-use error::ErrorCode;
-use schema::{ProposalList, Schema as BallotSchema};
-use transactions::{Ballot, Vote};
+use {BallotService, SERVICE_NAME};
+use error::{Error as ServiceError, ErrorCode};
+use schema::{BallotConfig, BallotLimits, BallotStatus, Finalization, OpenBallots, Outcome,
+             ProposalAction, ProposalList, Schema as BallotSchema, Tally, Threshold};
+use transactions::{Ballot, ProposeConfig, Vote, VoteConfig, MAX_AMENDMENT_LEN,
+                   MAX_PROPOSALS_LEN, MIN_ACTIVATION_DELAY};
 use api::VoteRequest;
 use tests::common::*;
 
@@ -25,19 +30,38 @@ macro_rules! assert_error_code {
     }}
 }
 
+macro_rules! assert_error_details {
+    ($snapshot: expr, $tx_hash: expr, $details: expr) => {{
+        use exonum::blockchain::Schema;
+
+        let tx_error = Schema::new($snapshot)
+            .transaction_results()
+            .get($tx_hash)
+            .unwrap()
+            .unwrap_err();
+
+        assert_eq!(
+            Some($details),
+            tx_error.description().and_then(ServiceError::from_description)
+        );
+    }}
+}
+
 pub trait BallotTestKit {
     fn ballot_default() -> Self;
 
     fn find_ballot(&self, proposals_hash: &Hash) -> Option<Ballot>;
 
     fn votes(&self, proposals_hash: &Hash) -> Vec<Option<Vote>>;
+
+    fn assert_participation(&self, proposals_hash: &Hash, electorate_size: u32, votes_cast: u32);
 }
 
 impl BallotTestKit for TestKit {
     fn ballot_default() -> Self {
         TestKitBuilder::validator()
             .with_validators(4)
-            .with_service(BallotService)
+            .with_service(BallotService::new())
             .create()
     }
 
@@ -52,6 +76,16 @@ impl BallotTestKit for TestKit {
         let schema = BallotSchema::new(&snapshot);
         schema.votes(proposals_hash)
     }
+
+    fn assert_participation(&self, proposals_hash: &Hash, electorate_size: u32, votes_cast: u32) {
+        let snapshot = self.snapshot();
+        let ballot_data = BallotSchema::new(&snapshot)
+            .ballot_data_by_proposals_hash()
+            .get(proposals_hash)
+            .expect("Data for ballot is absent");
+        assert_eq!(electorate_size, ballot_data.electorate_size());
+        assert_eq!(votes_cast, ballot_data.votes_cast());
+    }
 }
 
 #[test]
@@ -63,6 +97,24 @@ fn test_post_ballot() {
     testkit.create_block_with_transaction(tx_ballot.clone());
 
     assert_eq!(tx_ballot, testkit.find_ballot(&proposals.hash()).unwrap());
+    testkit.assert_participation(&proposals.hash(), 4, 0);
+
+    let snapshot = testkit.snapshot();
+    let schema = BallotSchema::new(&snapshot);
+    assert_eq!(
+        vec![0],
+        schema
+            .ordinals_by_author(tx_ballot.from())
+            .iter()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![0],
+        schema
+            .ordinals_by_deadline(proposals.deadline())
+            .iter()
+            .collect::<Vec<_>>()
+    );
 }
 
 #[test]
@@ -105,6 +157,898 @@ fn test_post_ballot_with_invalid_proposals() {
     );
 }
 
+#[test]
+fn test_post_ballot_exceeding_limits() {
+    let limits = BallotLimits {
+        max_proposals: 2,
+        max_subject_len: 8,
+        max_description_len: 16,
+        min_deadline_distance: 5,
+        max_deadline_distance: 100,
+        max_attachment_size: 5,
+        ..Default::default()
+    };
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(4)
+        .with_service(BallotService::with_config(BallotConfig {
+            limits: limits.clone(),
+            ..Default::default()
+        }))
+        .create();
+    assert_eq!(limits, BallotSchema::new(testkit.snapshot()).limits());
+
+    let cases = vec![
+        (
+            r#"{"id": 1, "deadline": 50, "proposals": []}"#,
+            ServiceError::NoProposals,
+        ),
+        (
+            r#"{"id": 2, "deadline": 50, "proposals": [
+                {"id": 1, "subject": "triss", "description": "magic"}
+              , {"id": 2, "subject": "ciri", "description": "queen"}
+              , {"id": 3, "subject": "yennefer", "description": "magic"}
+            ]}"#,
+            ServiceError::TooManyProposals { count: 3, max: 2 },
+        ),
+        (
+            r#"{"id": 3, "deadline": 50, "proposals": [{"id": 1, "subject": "  ", "description": "magic"}]}"#,
+            ServiceError::EmptySubject { proposal_id: 1 },
+        ),
+        (
+            r#"{"id": 4, "deadline": 50, "proposals": [{"id": 7, "subject": "yennefer of vengerberg", "description": "magic"}]}"#,
+            ServiceError::SubjectTooLong {
+                proposal_id: 7,
+                len: 22,
+                max: 8,
+            },
+        ),
+        (
+            r#"{"id": 5, "deadline": 50, "proposals": [{"id": 1, "subject": "ciri", "description": "lady of space and time"}]}"#,
+            ServiceError::DescriptionTooLong {
+                proposal_id: 1,
+                len: 22,
+                max: 16,
+            },
+        ),
+        (
+            r#"{"id": 6, "deadline": 3, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}]}"#,
+            ServiceError::DeadlineTooSoon {
+                deadline: 3,
+                height: 0,
+                min_deadline: 5,
+            },
+        ),
+        (
+            r#"{"id": 7, "deadline": 101, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}]}"#,
+            ServiceError::DeadlineTooFar {
+                deadline: 101,
+                height: 0,
+                max_deadline: 100,
+            },
+        ),
+        (
+            r#"{"id": 8, "deadline": 50, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}], "title": "the witcher"}"#,
+            ServiceError::InvalidMetadata {
+                message: "title has to be 1 to 8 bytes long".to_string(),
+            },
+        ),
+        (
+            r#"{"id": 9, "deadline": 50, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}], "tags": ["Bad Tag"]}"#,
+            ServiceError::InvalidMetadata {
+                message: r#""Bad Tag" isn't a valid category or tag"#.to_string(),
+            },
+        ),
+        (
+            r#"{"id": 10, "deadline": 50, "proposals": [{"id": 1, "subject": "ciri", "description": "queen"}], "parent": "0000000000000000000000000000000000000000000000000000000000000000"}"#,
+            ServiceError::ParentBallotNoneExists {
+                parent: Hash::zero(),
+            },
+        ),
+        (
+            r#"{"id": 11, "deadline": 50, "proposals": [{"id": 1, "subject": "ciri", "description": "queen", "attachments": [{"hash": "0000000000000000000000000000000000000000000000000000000000000000", "size": 5, "mime_type": "pdf"}]}]}"#,
+            ServiceError::InvalidAttachment {
+                proposal_id: 1,
+                message: r#""pdf" isn't a valid mime type"#.to_string(),
+            },
+        ),
+        (
+            r#"{"id": 12, "deadline": 50, "proposals": [{"id": 2, "subject": "ciri", "description": "queen", "attachments": [{"hash": "0000000000000000000000000000000000000000000000000000000000000000", "size": 5, "mime_type": "text/plain"}, {"hash": "0000000000000000000000000000000000000000000000000000000000000000", "size": 5, "mime_type": "text/html"}]}]}"#,
+            ServiceError::InvalidAttachment {
+                proposal_id: 2,
+                message: format!("attachment {} repeats", Hash::zero()),
+            },
+        ),
+        (
+            r#"{"id": 13, "deadline": 50, "proposals": [], "questions": [
+                {"id": 1, "subject": "budget", "proposals": [
+                    {"id": 1, "subject": "yes", "description": "raise"}
+                  , {"id": 2, "subject": "no", "description": "keep"}
+                ]}
+              , {"id": 2, "subject": "board", "proposals": [
+                    {"id": 1, "subject": "ciri", "description": "queen"}
+                ]}
+            ]}"#,
+            ServiceError::TooManyProposals { count: 3, max: 2 },
+        ),
+        (
+            r#"{"id": 14, "deadline": 50, "proposals": [{"id": 1, "subject": "ciri", "description": "queen", "attachments": [{"hash": "0000000000000000000000000000000000000000000000000000000000000000", "size": 6, "mime_type": "text/plain"}]}]}"#,
+            ServiceError::InvalidAttachment {
+                proposal_id: 1,
+                message: format!("attachment {} is larger than 5 bytes", Hash::zero()),
+            },
+        ),
+    ];
+
+    let keypair = testkit.network().validators()[1].service_keypair();
+    let tx_ballot_list = cases
+        .iter()
+        .map(|&(proposals_str, _)| Ballot::new(&keypair.0, proposals_str, &keypair.1))
+        .collect::<Vec<_>>();
+    let (_, proposals) = new_proposals_data();
+    let tx_valid_ballot = new_tx_ballot(&testkit.network().validators()[1], proposals.clone());
+    testkit.create_block_with_transactions(
+        tx_ballot_list
+            .iter()
+            .cloned()
+            .map(|tx| Box::new(tx) as Box<Transaction>)
+            .chain(Some(Box::new(tx_valid_ballot.clone()) as Box<Transaction>))
+            .collect::<Vec<_>>(),
+    );
+
+    let snapshot = testkit.snapshot();
+    for (tx_ballot, (_, details)) in tx_ballot_list.iter().zip(cases) {
+        assert_error_code!(&snapshot, &tx_ballot.hash(), details.code());
+        assert_error_details!(&snapshot, &tx_ballot.hash(), details);
+    }
+    assert_eq!(
+        tx_valid_ballot,
+        testkit.find_ballot(&proposals.hash()).unwrap()
+    );
+}
+
+#[test]
+fn test_post_ballot_exceeding_json_len() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let proposals_str = format!(
+        r#"{{"id": 1, "deadline": 50, "proposals": [{{"id": 1, "subject": "ciri", "description": "{}"}}]}}"#,
+        "a".repeat(MAX_PROPOSALS_LEN)
+    );
+    let keypair = testkit.network().validators()[1].service_keypair();
+    let tx_ballot = Ballot::new(&keypair.0, &proposals_str, &keypair.1);
+    testkit.create_block_with_transaction(tx_ballot.clone());
+
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_ballot.hash(),
+        ServiceError::ProposalsTooLong {
+            len: proposals_str.len() as u64,
+            max: MAX_PROPOSALS_LEN as u64,
+        }
+    );
+}
+
+#[test]
+fn test_post_ballot_rate_limits() {
+    use exonum::helpers::Height;
+
+    let limits = BallotLimits {
+        max_open_ballots: 2,
+        min_ballot_interval: 3,
+        ..Default::default()
+    };
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(4)
+        .with_service(BallotService::with_config(BallotConfig {
+            limits,
+            ..Default::default()
+        }))
+        .create();
+    let validators = testkit.network().validators().to_vec();
+    let new_ballot = |node: &TestNode, id: u64| {
+        let proposals_str = format!(
+            r#"{{"id": {}, "deadline": 30, "proposals": [{{"id": 1, "subject": "ciri", "description": "queen"}}]}}"#,
+            id
+        );
+        let proposals = ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap();
+        new_tx_ballot(node, proposals)
+    };
+
+    testkit.create_block_with_transaction(new_ballot(&validators[0], 1));
+    let tx_early = new_ballot(&validators[0], 2);
+    testkit.create_block_with_transaction(tx_early.clone());
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_early.hash(),
+        ServiceError::BallotIntervalTooShort {
+            height: 2,
+            last_height: 1,
+            min_interval: 3,
+        }
+    );
+
+    testkit.create_blocks_until(Height(3));
+    testkit.create_block_with_transaction(tx_early.clone());
+    assert_eq!(
+        Some(4),
+        BallotSchema::new(testkit.snapshot())
+            .last_ballot_heights()
+            .get(validators[0].service_keypair().0)
+    );
+
+    // Other authors aren't limited by the ballots of the first one.
+    testkit.create_blocks_until(Height(6));
+    let tx_excess = new_ballot(&validators[0], 3);
+    let tx_other = new_ballot(&validators[1], 4);
+    testkit.create_block_with_transactions(txvec![tx_excess.clone(), tx_other.clone()]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_excess.hash(),
+        ServiceError::TooManyOpenBallots { open: 2, max: 2 }
+    );
+    assert_eq!(
+        Some(OpenBallots { deadlines: vec![30, 30] }),
+        BallotSchema::new(testkit.snapshot())
+            .open_ballots()
+            .get(validators[0].service_keypair().0)
+    );
+    assert!(
+        CoreSchema::new(testkit.snapshot())
+            .transaction_results()
+            .get(&tx_other.hash())
+            .unwrap()
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_sponsor_ballot() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let new_proposals = |min_sponsors: u32| {
+        let proposals_str = format!(
+            r#"{{"id": 1, "deadline": 30, "min_sponsors": {}, "proposals": [
+                    {{"id": 1, "subject": "triss", "description": "magic"}}
+                  , {{"id": 2, "subject": "ciri", "description": "queen"}}
+                  ]}}"#,
+            min_sponsors
+        );
+        ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap()
+    };
+    let proposals = new_proposals(2);
+    let proposals_hash = proposals.hash();
+    let tx_unreachable = new_tx_ballot(&validators[1], new_proposals(4));
+    testkit.create_block_with_transactions(txvec![
+        new_tx_ballot(&validators[0], proposals),
+        tx_unreachable.clone(),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_unreachable.hash(),
+        ServiceError::SponsorsUnreachable {
+            min_sponsors: 4,
+            eligible: 3,
+        }
+    );
+
+    let vote = |node: &TestNode, proposal_id: u64, subject: &str| {
+        let vote_req = VoteRequest {
+            proposal_id,
+            proposal_subject: subject.to_string(),
+        };
+        new_tx_vote(node, &proposals_hash, &vote_req)
+    };
+    let tx_early_vote = vote(&validators[1], 1, "triss");
+    let tx_author_sponsor = new_tx_sponsor(&validators[0], &proposals_hash);
+    testkit.create_block_with_transactions(txvec![
+        tx_early_vote.clone(),
+        tx_author_sponsor.clone(),
+        new_tx_sponsor(&validators[1], &proposals_hash),
+    ]);
+    {
+        let snapshot = testkit.snapshot();
+        assert_error_details!(
+            &snapshot,
+            &tx_early_vote.hash(),
+            ServiceError::BallotSeekingSponsors {
+                proposals_hash,
+                sponsors: 0,
+                min_sponsors: 2,
+            }
+        );
+        assert_error_details!(
+            &snapshot,
+            &tx_author_sponsor.hash(),
+            ServiceError::AlreadySponsored {
+                sponsor: *validators[0].service_keypair().0,
+            }
+        );
+        assert_eq!(
+            Some(BallotStatus::SeekingSponsors),
+            BallotSchema::new(&snapshot).ballot_status(&proposals_hash)
+        );
+    }
+
+    testkit.create_block_with_transaction(new_tx_sponsor(&validators[2], &proposals_hash));
+    let opened_at = testkit.height().0;
+    {
+        let snapshot = testkit.snapshot();
+        let schema = BallotSchema::new(&snapshot);
+        assert_eq!(
+            Some(BallotStatus::Open),
+            schema.ballot_status(&proposals_hash)
+        );
+        let sponsorship = schema.sponsorships().get(&proposals_hash).unwrap();
+        assert_eq!(Some(opened_at), sponsorship.opened_at);
+        assert_eq!(
+            vec![
+                *validators[1].service_keypair().0,
+                *validators[2].service_keypair().0,
+            ],
+            sponsorship.sponsors
+        );
+    }
+
+    let tx_late_sponsor = new_tx_sponsor(&validators[3], &proposals_hash);
+    testkit.create_block_with_transactions(txvec![
+        tx_late_sponsor.clone(),
+        vote(&validators[1], 2, "ciri"),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_late_sponsor.hash(),
+        ServiceError::NotSeekingSponsors { proposals_hash }
+    );
+    testkit.assert_participation(&proposals_hash, 4, 1);
+}
+
+#[test]
+fn test_amend_ballot() {
+    use exonum::helpers::Height;
+
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let new_proposals = |discussion_until: u64| {
+        let proposals_str = format!(
+            r#"{{"id": 1, "deadline": 30, "discussion_until": {}, "proposals": [
+                    {{"id": 1, "subject": "triss", "description": "magic"}}
+                  , {{"id": 2, "subject": "ciri", "description": "queen"}}
+                  ]}}"#,
+            discussion_until
+        );
+        ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap()
+    };
+    let proposals = new_proposals(5);
+    let proposals_hash = proposals.hash();
+    let tx_out_of_range = new_tx_ballot(&validators[1], new_proposals(40));
+    testkit.create_block_with_transactions(txvec![
+        new_tx_ballot(&validators[0], proposals),
+        tx_out_of_range.clone(),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_out_of_range.hash(),
+        ServiceError::DiscussionOutOfRange {
+            discussion_until: 40,
+            height: 0,
+            deadline: 30,
+        }
+    );
+    assert_eq!(
+        Some(BallotStatus::InDiscussion),
+        BallotSchema::new(testkit.snapshot()).ballot_status(&proposals_hash)
+    );
+
+    let vote = |node: &TestNode, proposal_id: u64, subject: &str| {
+        let vote_req = VoteRequest {
+            proposal_id,
+            proposal_subject: subject.to_string(),
+        };
+        new_tx_vote(node, &proposals_hash, &vote_req)
+    };
+    let edit = r#"{"type": "edit", "proposal": {"id": 1, "subject": "triss merigold", "description": "magic"}}"#;
+    let tx_foreign = new_tx_amend(&validators[1], &proposals_hash, edit);
+    let tx_missing = new_tx_amend(
+        &validators[0],
+        &proposals_hash,
+        r#"{"type": "withdraw", "proposal_id": 7}"#,
+    );
+    let tx_early_vote = vote(&validators[2], 2, "ciri");
+    testkit.create_block_with_transactions(txvec![
+        tx_foreign.clone(),
+        new_tx_amend(&validators[0], &proposals_hash, edit),
+        tx_missing.clone(),
+        tx_early_vote.clone(),
+    ]);
+    {
+        let snapshot = testkit.snapshot();
+        assert_error_details!(
+            &snapshot,
+            &tx_foreign.hash(),
+            ServiceError::NotBallotAuthor {
+                sender: *validators[1].service_keypair().0,
+            }
+        );
+        assert_error_details!(
+            &snapshot,
+            &tx_missing.hash(),
+            ServiceError::AmendedProposalNoneExists { proposal_id: 7 }
+        );
+        assert_error_details!(
+            &snapshot,
+            &tx_early_vote.hash(),
+            ServiceError::BallotInDiscussion {
+                discussion_until: 5,
+                height: 1,
+            }
+        );
+    }
+
+    let tx_duplicate = new_tx_amend(
+        &validators[0],
+        &proposals_hash,
+        r#"{"type": "add", "proposal": {"id": 2, "subject": "yennefer", "description": "magic"}}"#,
+    );
+    testkit.create_block_with_transactions(txvec![
+        tx_duplicate.clone(),
+        new_tx_amend(
+            &validators[0],
+            &proposals_hash,
+            r#"{"type": "add", "proposal": {"id": 3, "subject": "yennefer", "description": "magic"}}"#,
+        ),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_duplicate.hash(),
+        ServiceError::PostDuplicateProposalId { proposal_id: 2 }
+    );
+    {
+        let snapshot = testkit.snapshot();
+        let schema = BallotSchema::new(&snapshot);
+        let revisions = schema.revisions(&proposals_hash).iter().collect::<Vec<_>>();
+        assert_eq!(
+            vec![2, 3],
+            revisions
+                .iter()
+                .map(|revision| revision.height)
+                .collect::<Vec<_>>()
+        );
+        let proposals = schema.proposals(&proposals_hash).unwrap();
+        assert_eq!(revisions[1].hash, proposals.hash());
+        assert!(proposals.contains(1, "triss merigold"));
+        assert!(proposals.contains(3, "yennefer"));
+        assert_eq!(3, proposals.proposals().len());
+    }
+
+    testkit.create_blocks_until(Height(6));
+    let tx_late_amend = new_tx_amend(
+        &validators[0],
+        &proposals_hash,
+        r#"{"type": "withdraw", "proposal_id": 2}"#,
+    );
+    let tx_stale_vote = vote(&validators[1], 1, "triss");
+    testkit.create_block_with_transactions(txvec![
+        tx_late_amend.clone(),
+        tx_stale_vote.clone(),
+        vote(&validators[2], 1, "triss merigold"),
+    ]);
+    {
+        let snapshot = testkit.snapshot();
+        assert_error_details!(
+            &snapshot,
+            &tx_late_amend.hash(),
+            ServiceError::DiscussionClosed {
+                proposals_hash,
+                height: 6,
+            }
+        );
+        assert_error_code!(
+            &snapshot,
+            &tx_stale_vote.hash(),
+            ErrorCode::VotedProposalNoneExists
+        );
+        let schema = BallotSchema::new(&snapshot);
+        assert_eq!(
+            Some(BallotStatus::Open),
+            schema.ballot_status(&proposals_hash)
+        );
+        assert_eq!(1, schema.tallies().get(&proposals_hash).unwrap().votes(1));
+    }
+}
+
+#[test]
+fn test_amend_ballot_limits() {
+    use transactions::AmendBallot;
+
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(4)
+        .with_service(BallotService::with_config(BallotConfig {
+            limits: BallotLimits {
+                max_revisions: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .create();
+    let validators = testkit.network().validators().to_vec();
+    let proposals_str = r#"{"id": 1, "deadline": 30, "discussion_until": 5, "proposals": [
+                                {"id": 1, "subject": "triss", "description": "magic"}
+                            ]}"#;
+    let proposals = ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap();
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+
+    let keypair = validators[0].service_keypair();
+    let long_amendment = format!(
+        r#"{{"type": "edit", "proposal": {{"id": 1, "subject": "triss", "description": "{}"}}}}"#,
+        "a".repeat(MAX_AMENDMENT_LEN)
+    );
+    let tx_long = AmendBallot::new(&keypair.0, &proposals_hash, &long_amendment, &keypair.1);
+    let tx_excess = new_tx_amend(
+        &validators[0],
+        &proposals_hash,
+        r#"{"type": "add", "proposal": {"id": 3, "subject": "geralt", "description": "witcher"}}"#,
+    );
+    testkit.create_block_with_transactions(txvec![
+        tx_long.clone(),
+        new_tx_amend(
+            &validators[0],
+            &proposals_hash,
+            r#"{"type": "add", "proposal": {"id": 2, "subject": "ciri", "description": "queen"}}"#,
+        ),
+        tx_excess.clone(),
+    ]);
+
+    let snapshot = testkit.snapshot();
+    assert_error_details!(
+        &snapshot,
+        &tx_long.hash(),
+        ServiceError::AmendmentTooLong {
+            len: long_amendment.len() as u64,
+            max: MAX_AMENDMENT_LEN as u64,
+        }
+    );
+    assert_error_details!(
+        &snapshot,
+        &tx_excess.hash(),
+        ServiceError::TooManyRevisions {
+            proposals_hash,
+            revisions: 1,
+            max: 1,
+        }
+    );
+    let schema = BallotSchema::new(&snapshot);
+    assert_eq!(1, schema.revisions(&proposals_hash).len());
+    assert_eq!(
+        Some(schema.revisions(&proposals_hash).merkle_root()),
+        schema.revision_roots().get(&proposals_hash)
+    );
+}
+
+#[test]
+fn test_nominate_proposals() {
+    use exonum::helpers::Height;
+    use transactions::Nominate;
+
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let proposals_str = r#"{"id": 1, "deadline": 30, "nominations_until": 3, "proposals": [
+                                {"id": 1, "subject": "triss", "description": "magic"}
+                              , {"id": 5, "subject": "ciri", "description": "queen"}
+                            ]}"#;
+    let proposals = ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap();
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+    assert_eq!(
+        Some(BallotStatus::Nominating),
+        BallotSchema::new(testkit.snapshot()).ballot_status(&proposals_hash)
+    );
+
+    let tx_unknown_sender = {
+        let keypair = crypto::gen_keypair();
+        Nominate::new(&keypair.0, &proposals_hash, "eskel", "witcher", &keypair.1)
+    };
+    let tx_empty = new_tx_nominate(&validators[3], &proposals_hash, " ");
+    let vote_req = VoteRequest {
+        proposal_id: 6,
+        proposal_subject: "yennefer".to_string(),
+    };
+    let tx_early_vote = new_tx_vote(&validators[3], &proposals_hash, &vote_req);
+    testkit.create_block_with_transactions(txvec![
+        new_tx_nominate(&validators[1], &proposals_hash, "yennefer"),
+        new_tx_nominate(&validators[2], &proposals_hash, "geralt"),
+        tx_unknown_sender.clone(),
+        tx_empty.clone(),
+        tx_early_vote.clone(),
+    ]);
+    {
+        let snapshot = testkit.snapshot();
+        assert_error_code!(
+            &snapshot,
+            &tx_unknown_sender.hash(),
+            ErrorCode::UnknownSender
+        );
+        assert_error_details!(
+            &snapshot,
+            &tx_empty.hash(),
+            ServiceError::EmptySubject { proposal_id: 8 }
+        );
+        assert_error_details!(
+            &snapshot,
+            &tx_early_vote.hash(),
+            ServiceError::BallotNominating {
+                nominations_until: 3,
+                height: 1,
+            }
+        );
+
+        let schema = BallotSchema::new(&snapshot);
+        let proposals = schema.proposals(&proposals_hash).unwrap();
+        assert!(proposals.contains(6, "yennefer"));
+        assert!(proposals.contains(7, "geralt"));
+        assert_eq!(
+            vec![
+                *validators[1].service_keypair().0,
+                *validators[2].service_keypair().0,
+            ],
+            schema
+                .revisions(&proposals_hash)
+                .iter()
+                .map(|revision| revision.from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    testkit.create_blocks_until(Height(4));
+    let tx_late = new_tx_nominate(&validators[3], &proposals_hash, "eskel");
+    testkit.create_block_with_transactions(txvec![
+        tx_late.clone(),
+        new_tx_vote(&validators[2], &proposals_hash, &vote_req),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_late.hash(),
+        ServiceError::NominationsClosed {
+            proposals_hash,
+            height: 4,
+        }
+    );
+    let tally = BallotSchema::new(testkit.snapshot())
+        .tallies()
+        .get(&proposals_hash)
+        .unwrap();
+    assert_eq!(1, tally.votes(6));
+}
+
+#[test]
+fn test_nomination_limits() {
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(4)
+        .with_service(BallotService::with_config(BallotConfig {
+            limits: BallotLimits {
+                max_nominations_per_voter: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .create();
+    let validators = testkit.network().validators().to_vec();
+    let proposals_str = r#"{"id": 1, "deadline": 30, "discussion_until": 5,
+                            "nominations_until": 5, "proposals": [
+                                {"id": 1, "subject": "triss", "description": "magic"}
+                            ]}"#;
+    let proposals = ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap();
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+
+    let tx_excess = new_tx_nominate(&validators[1], &proposals_hash, "geralt");
+    testkit.create_block_with_transactions(txvec![
+        new_tx_nominate(&validators[1], &proposals_hash, "yennefer"),
+        tx_excess.clone(),
+        new_tx_nominate(&validators[0], &proposals_hash, "eskel"),
+        new_tx_nominate(&validators[0], &proposals_hash, "lambert"),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_excess.hash(),
+        ServiceError::TooManyNominations {
+            nominations: 1,
+            max: 1,
+        }
+    );
+
+    let tx_withdraw_nominated = new_tx_amend(
+        &validators[0],
+        &proposals_hash,
+        r#"{"type": "withdraw", "proposal_id": 2}"#,
+    );
+    testkit.create_block_with_transactions(txvec![
+        tx_withdraw_nominated.clone(),
+        new_tx_amend(
+            &validators[0],
+            &proposals_hash,
+            r#"{"type": "withdraw", "proposal_id": 3}"#,
+        ),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_withdraw_nominated.hash(),
+        ServiceError::NominatedProposal {
+            proposal_id: 2,
+            nominator: *validators[1].service_keypair().0,
+        }
+    );
+
+    let snapshot = testkit.snapshot();
+    let proposals = BallotSchema::new(&snapshot).proposals(&proposals_hash).unwrap();
+    assert!(proposals.contains(2, "yennefer"));
+    assert!(!proposals.contains(3, "eskel"));
+    assert!(proposals.contains(4, "lambert"));
+}
+
+#[test]
+fn test_vote_questions() {
+    use exonum::helpers::Height;
+    use schema::{question_tally_key, QuestionOutcome};
+
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let proposals_str = r#"{"id": 1, "deadline": 10, "proposals": [], "questions": [
+                                {"id": 1, "subject": "budget", "proposals": [
+                                    {"id": 1, "subject": "yes", "description": "raise"}
+                                  , {"id": 2, "subject": "no", "description": "keep"}
+                                ]}
+                              , {"id": 2, "subject": "board", "mode": {"type": "approval",
+                                 "max_choices": 2}, "proposals": [
+                                    {"id": 1, "subject": "triss", "description": "magic"}
+                                  , {"id": 2, "subject": "ciri", "description": "queen"}
+                                  , {"id": 3, "subject": "yennefer", "description": "magic"}
+                                ]}
+                            ]}"#;
+    let proposals = ProposalList::try_deserialize(proposals_str.as_bytes()).unwrap();
+    let proposals_hash = proposals.hash();
+    let no_choices_str = r#"{"id": 2, "deadline": 10, "proposals": [], "questions": [
+                                {"id": 1, "subject": "board", "mode": {"type": "approval",
+                                 "max_choices": 0}, "proposals": [
+                                    {"id": 1, "subject": "triss", "description": "magic"}
+                                ]}
+                            ]}"#;
+    let no_choices = ProposalList::try_deserialize(no_choices_str.as_bytes()).unwrap();
+    let tx_no_choices = new_tx_ballot(&validators[1], no_choices);
+    testkit.create_block_with_transactions(txvec![
+        new_tx_ballot(&validators[0], proposals),
+        tx_no_choices.clone(),
+    ]);
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_no_choices.hash(),
+        ServiceError::InvalidQuestions {
+            message: "question 1 allows no choices".to_string(),
+        }
+    );
+
+    let tx_unanswered = new_tx_answers(
+        &validators[2],
+        &proposals_hash,
+        r#"[{"question_id": 1, "proposal_ids": [2]}]"#,
+    );
+    let tx_two_choices = new_tx_answers(
+        &validators[3],
+        &proposals_hash,
+        r#"[{"question_id": 1, "proposal_ids": [1, 2]}, {"question_id": 2, "proposal_ids": [1]}]"#,
+    );
+    let tx_again = new_tx_answers(
+        &validators[0],
+        &proposals_hash,
+        r#"[{"question_id": 1, "proposal_ids": [2]}, {"question_id": 2, "proposal_ids": [2]}]"#,
+    );
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "yes".to_string(),
+    };
+    let tx_vote = new_tx_vote(&validators[3], &proposals_hash, &vote_req);
+    testkit.create_block_with_transactions(txvec![
+        new_tx_answers(
+            &validators[0],
+            &proposals_hash,
+            r#"[{"question_id": 1, "proposal_ids": [1]}, {"question_id": 2, "proposal_ids": [1, 3]}]"#,
+        ),
+        new_tx_answers(
+            &validators[1],
+            &proposals_hash,
+            r#"[{"question_id": 2, "proposal_ids": [2]}, {"question_id": 1, "proposal_ids": [1]}]"#,
+        ),
+        tx_unanswered.clone(),
+        tx_two_choices.clone(),
+        tx_again.clone(),
+        tx_vote.clone(),
+    ]);
+    {
+        let snapshot = testkit.snapshot();
+        assert_error_details!(
+            &snapshot,
+            &tx_unanswered.hash(),
+            ServiceError::InvalidAnswers {
+                message: "question 2 isn't answered".to_string(),
+            }
+        );
+        assert_error_details!(
+            &snapshot,
+            &tx_two_choices.hash(),
+            ServiceError::InvalidAnswers {
+                message: "question 1 takes 1 to 1 proposals, got 2".to_string(),
+            }
+        );
+        assert_error_details!(
+            &snapshot,
+            &tx_again.hash(),
+            ServiceError::AlreadyVoted {
+                voter: *validators[0].service_keypair().0,
+            }
+        );
+        assert_error_code!(
+            &snapshot,
+            &tx_vote.hash(),
+            ErrorCode::VotedProposalNoneExists
+        );
+
+        let schema = BallotSchema::new(&snapshot);
+        let tallies = schema.tallies();
+        let budget = tallies
+            .get(&question_tally_key(&proposals_hash, 1))
+            .unwrap();
+        assert_eq!((2, 0), (budget.votes(1), budget.votes(2)));
+        let board = tallies
+            .get(&question_tally_key(&proposals_hash, 2))
+            .unwrap();
+        assert_eq!((1, 1, 1), (board.votes(1), board.votes(2), board.votes(3)));
+        assert_eq!(
+            2,
+            schema
+                .ballot_data_by_proposals_hash()
+                .get(&proposals_hash)
+                .unwrap()
+                .votes_cast()
+        );
+        assert!(schema.has_voted(&proposals_hash, validators[1].service_keypair().0));
+        assert!(!schema.has_voted(&proposals_hash, validators[2].service_keypair().0));
+        assert_eq!(
+            vec![proposals_hash],
+            schema
+                .answers_by_voter(validators[1].service_keypair().0)
+                .iter()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    testkit.create_blocks_until(Height(11));
+    testkit.create_block_with_transaction(new_tx_finalize(&validators[2], &proposals_hash));
+    let finalization = BallotSchema::new(testkit.snapshot())
+        .finalizations()
+        .get(&proposals_hash)
+        .unwrap();
+    assert_eq!(Outcome::NoVotes, finalization.outcome);
+    assert!(!finalization.quorum_reached);
+    assert_eq!(
+        vec![
+            QuestionOutcome {
+                question_id: 1,
+                outcome: Outcome::Winner { proposal_id: 1 },
+            },
+            QuestionOutcome {
+                question_id: 2,
+                outcome: Outcome::Tie {
+                    proposal_ids: vec![1, 2, 3],
+                },
+            },
+        ],
+        finalization.questions
+    );
+}
+
 #[test]
 fn test_post_duplicate_ballot() {
     let mut testkit: TestKit = TestKit::ballot_default();
@@ -139,6 +1083,11 @@ fn test_post_ballot_with_duplicate_proposal_id() {
         &tx_ballot.hash(),
         ErrorCode::PostDuplicateProposalId
     );
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_ballot.hash(),
+        ServiceError::PostDuplicateProposalId { proposal_id: 1 }
+    );
 }
 
 #[test]
@@ -162,7 +1111,99 @@ fn test_post_vote() {
 
     testkit.create_block_with_transaction(tx_vote.clone());
     let votes = testkit.votes(&proposals_hash);
-    assert!(votes.contains(&Some(tx_vote)));
+    assert!(votes.contains(&Some(tx_vote.clone())));
+    assert_votes_history_consistent(&testkit.snapshot());
+
+    let snapshot = testkit.snapshot();
+    let records = BallotSchema::new(&snapshot)
+        .votes_by_voter(tx_vote.from())
+        .iter()
+        .collect::<Vec<_>>();
+    assert_eq!(1, records.len());
+    assert_eq!(tx_vote, records[0].tx_vote());
+    assert_eq!(testkit.height().0, records[0].height());
+
+    let tally = BallotSchema::new(&snapshot)
+        .tallies()
+        .get(&proposals_hash)
+        .unwrap();
+    assert_eq!(1, tally.votes(1));
+    assert_eq!(1, tally.votes_cast());
+}
+
+#[test]
+fn test_votes_history_hash_consistency() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+
+    let (_, proposals) = create_test_ballot!(testkit);
+    let proposals_hash = proposals.hash();
+    assert_votes_history_consistent(&testkit.snapshot());
+
+    let vote_req = VoteRequest {
+        proposal_id: 2,
+        proposal_subject: "ciri".to_string(),
+    };
+    let validators = testkit.network().validators().to_vec();
+    for (votes_cast, validator) in validators.iter().rev().enumerate() {
+        let tx_vote = new_tx_vote(validator, &proposals_hash, &vote_req);
+        testkit.create_block_with_transaction(tx_vote);
+        assert_votes_history_consistent(&testkit.snapshot());
+        testkit.assert_participation(&proposals_hash, 4, votes_cast as u32 + 1);
+    }
+
+    let votes = testkit.votes(&proposals_hash);
+    assert!(votes.iter().all(Option::is_some));
+}
+
+#[test]
+fn test_tally_outcome() {
+    let testkit: TestKit = TestKit::ballot_default();
+    let (_, proposals) = new_proposals_data();
+    let proposals_hash = proposals.hash();
+    let validators = testkit.network().validators();
+    let vote = |idx: usize, proposal_id: u64, subject: &str| {
+        let vote_req = VoteRequest {
+            proposal_id,
+            proposal_subject: subject.to_string(),
+        };
+        Some(new_tx_vote(&validators[idx], &proposals_hash, &vote_req))
+    };
+
+    let tally = Tally::from_votes(&proposals, &[None, None]);
+    assert_eq!(Outcome::NoVotes, tally.outcome());
+
+    let votes = [vote(0, 1, "triss"), vote(1, 3, "yennefer"), None];
+    let tally = Tally::from_votes(&proposals, &votes);
+    assert_eq!(2, tally.votes_cast());
+    assert_eq!(
+        Outcome::Tie {
+            proposal_ids: vec![1, 3],
+        },
+        tally.outcome()
+    );
+
+    let quorum = BallotConfig::default().quorum;
+    assert_eq!(3, quorum.required(4));
+    assert_eq!(3, quorum.required(3));
+    assert_eq!(1, quorum.required(1));
+    assert_eq!(
+        u64::max_value() / 3 * 2 + 1,
+        quorum.required(u64::max_value())
+    );
+
+    let quorum = Threshold {
+        numerator: 999,
+        denominator: 1000,
+    };
+    assert!(quorum.is_valid());
+    assert_eq!(
+        u64::max_value() - u64::max_value() / 1000,
+        quorum.required(u64::max_value())
+    );
+    assert!(!Threshold {
+        numerator: 1000,
+        denominator: 1001,
+    }.is_valid());
 }
 
 #[test]
@@ -292,4 +1333,618 @@ fn test_post_vote_for_closed_ballot() {
         &tx_vote.hash(),
         ErrorCode::BallotAlreadyClosed
     );
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_vote.hash(),
+        ServiceError::BallotAlreadyClosed {
+            deadline: 30,
+            height: 50,
+        }
+    );
+}
+
+fn new_tx_propose_config(node: &TestNode, config: &BallotConfig) -> ProposeConfig {
+    let keypair = node.service_keypair();
+    ProposeConfig::new(
+        keypair.0,
+        &::serde_json::to_string(config).unwrap(),
+        keypair.1,
+    )
+}
+
+fn new_tx_vote_config(node: &TestNode, config_hash: &Hash) -> VoteConfig {
+    let keypair = node.service_keypair();
+    VoteConfig::new(keypair.0, config_hash, keypair.1)
+}
+
+#[test]
+fn test_genesis_config() {
+    use exonum::blockchain::Schema as CoreSchema;
+
+    let testkit: TestKit = TestKit::ballot_default();
+    let snapshot = testkit.snapshot();
+
+    assert_eq!(BallotConfig::default(), BallotSchema::new(&snapshot).config());
+    assert_eq!(
+        ::serde_json::to_value(BallotConfig::default()).unwrap(),
+        CoreSchema::new(&snapshot).actual_configuration().services[SERVICE_NAME]
+    );
+}
+
+#[test]
+fn test_config_json_omits_default_rate_limits() {
+    let json = ::serde_json::to_value(BallotConfig::default()).unwrap();
+    assert!(json["limits"].get("max_open_ballots").is_none());
+    assert!(json["limits"].get("min_ballot_interval").is_none());
+    assert_eq!(BallotConfig::default(), ::serde_json::from_value(json).unwrap());
+
+    let limits = BallotLimits {
+        max_open_ballots: 2,
+        min_ballot_interval: 3,
+        ..Default::default()
+    };
+    let json = ::serde_json::to_value(&limits).unwrap();
+    assert_eq!(2, json["max_open_ballots"]);
+    assert_eq!(3, json["min_ballot_interval"]);
+    assert_eq!(limits, ::serde_json::from_value(json).unwrap());
+}
+
+#[test]
+fn test_config_change() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let config = BallotConfig {
+        limits: BallotLimits {
+            max_proposals: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let config_hash = config.hash();
+    testkit.create_block_with_transaction(new_tx_propose_config(&validators[0], &config));
+    testkit.create_block_with_transaction(new_tx_vote_config(&validators[1], &config_hash));
+
+    // Two of four validators don't exceed two thirds.
+    let proposal = {
+        let snapshot = testkit.snapshot();
+        let schema = BallotSchema::new(&snapshot);
+        assert_eq!(BallotConfig::default(), schema.config());
+        schema.config_proposals().get(&config_hash).unwrap()
+    };
+    assert_eq!(2, proposal.approvals.len());
+    assert_eq!(None, proposal.applied_at);
+
+    testkit.create_block_with_transaction(new_tx_vote_config(&validators[2], &config_hash));
+    let applied_at = testkit.height().0;
+    {
+        let snapshot = testkit.snapshot();
+        let schema = BallotSchema::new(&snapshot);
+        assert_eq!(config, schema.config());
+        assert_eq!(
+            Some(applied_at),
+            schema.config_proposals().get(&config_hash).unwrap().applied_at
+        );
+    }
+
+    let tx_late_vote = new_tx_vote_config(&validators[3], &config_hash);
+    testkit.create_block_with_transaction(tx_late_vote.clone());
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_late_vote.hash(),
+        ServiceError::ConfigAlreadyApplied {
+            config_hash,
+            height: applied_at,
+        }
+    );
+
+    // The new limits apply to ballots posted afterwards.
+    let (_, proposals) = new_proposals_data();
+    let tx_ballot = new_tx_ballot(&validators[0], proposals);
+    testkit.create_block_with_transaction(tx_ballot.clone());
+    assert_error_code!(
+        &testkit.snapshot(),
+        &tx_ballot.hash(),
+        ErrorCode::TooManyProposals
+    );
+}
+
+#[test]
+fn test_config_change_errors() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let config = BallotConfig {
+        limits: BallotLimits {
+            max_proposals: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let config_hash = config.hash();
+    let invalid_config = BallotConfig {
+        quorum: Threshold {
+            numerator: 3,
+            denominator: 2,
+        },
+        ..Default::default()
+    };
+    let tx_unknown_sender = {
+        let keypair = crypto::gen_keypair();
+        ProposeConfig::new(
+            &keypair.0,
+            &::serde_json::to_string(&config).unwrap(),
+            &keypair.1,
+        )
+    };
+    let tx_invalid = new_tx_propose_config(&validators[0], &invalid_config);
+    let tx_propose = new_tx_propose_config(&validators[0], &config);
+    let tx_propose_again = new_tx_propose_config(&validators[1], &config);
+    let tx_vote_twice = new_tx_vote_config(&validators[0], &config_hash);
+    let tx_vote_unknown = new_tx_vote_config(&validators[1], &hash(&[1, 2, 3]));
+    testkit.create_block_with_transactions(txvec![
+        tx_unknown_sender.clone(),
+        tx_invalid.clone(),
+        tx_propose.clone(),
+        tx_propose_again.clone(),
+        tx_vote_twice.clone(),
+        tx_vote_unknown.clone(),
+    ]);
+
+    let snapshot = testkit.snapshot();
+    assert_error_code!(
+        &snapshot,
+        &tx_unknown_sender.hash(),
+        ErrorCode::UnknownSender
+    );
+    assert_error_details!(
+        &snapshot,
+        &tx_invalid.hash(),
+        ServiceError::InvalidConfig {
+            message: "quorum must be a fraction below 1 with a denominator up to 1000".to_string(),
+        }
+    );
+    assert_error_details!(
+        &snapshot,
+        &tx_propose_again.hash(),
+        ServiceError::ConfigAlreadyProposed { config_hash }
+    );
+    assert_error_code!(&snapshot, &tx_vote_twice.hash(), ErrorCode::AlreadyVoted);
+    assert_error_code!(
+        &snapshot,
+        &tx_vote_unknown.hash(),
+        ErrorCode::ConfigProposalNoneExists
+    );
+    assert_eq!(
+        1,
+        BallotSchema::new(&snapshot)
+            .config_proposals()
+            .get(&config_hash)
+            .unwrap()
+            .approvals
+            .len()
+    );
+}
+
+#[test]
+fn test_post_ballot_with_invalid_action() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+
+    let action = ProposalAction::UpdateConfig {
+        config: BallotConfig {
+            governance: Threshold {
+                numerator: 1,
+                denominator: 0,
+            },
+            ..Default::default()
+        },
+    };
+    let proposals = new_action_proposals(30, &action);
+    let tx_ballot = new_tx_ballot(&testkit.network().validators()[0], proposals);
+    testkit.create_block_with_transaction(tx_ballot.clone());
+
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_ballot.hash(),
+        ServiceError::InvalidAction {
+            proposal_id: 1,
+            message: "governance must be a fraction below 1 with a denominator up to 1000"
+                .to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_finalize_ballot_applies_action() {
+    use exonum::helpers::Height;
+
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let (new_voter, new_voter_secret) = crypto::gen_keypair();
+    let action = ProposalAction::AddVoter { key: new_voter };
+    let proposals = new_action_proposals(5, &action);
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "yes".to_string(),
+    };
+    let tx_votes = validators[..3]
+        .iter()
+        .map(|validator| new_tx_vote(validator, &proposals_hash, &vote_req))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_votes);
+
+    let tx_early = new_tx_finalize(&validators[1], &proposals_hash);
+    testkit.create_block_with_transaction(tx_early.clone());
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_early.hash(),
+        ServiceError::BallotNotClosed {
+            deadline: 5,
+            height: 2,
+        }
+    );
+
+    testkit.create_blocks_until(Height(6));
+    testkit.create_block_with_transaction(new_tx_finalize(&validators[1], &proposals_hash));
+    let finalized_at = testkit.height().0;
+    {
+        let snapshot = testkit.snapshot();
+        let schema = BallotSchema::new(&snapshot);
+        assert_eq!(
+            Some(Finalization {
+                height: finalized_at,
+                outcome: Outcome::Winner { proposal_id: 1 },
+                quorum_reached: true,
+                quorum: Some(3),
+                action: Some(action),
+                validator_config: None,
+                questions: Vec::new(),
+            }),
+            schema.finalizations().get(&proposals_hash)
+        );
+
+        let mut expected = validators
+            .iter()
+            .map(|validator| *validator.service_keypair().0)
+            .collect::<Vec<_>>();
+        expected.push(new_voter);
+        expected.sort();
+        let mut voters = schema.eligible_voters();
+        voters.sort();
+        assert_eq!(expected, voters);
+    }
+
+    let tx_again = new_tx_finalize(&validators[2], &proposals_hash);
+    testkit.create_block_with_transaction(tx_again.clone());
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_again.hash(),
+        ServiceError::BallotAlreadyFinalized {
+            proposals_hash,
+            height: finalized_at,
+        }
+    );
+
+    // The new voter may post, and ballots posted from now on reserve a slot for it.
+    let (proposals_str, proposals) = new_proposals_data();
+    let tx_ballot = Ballot::new(&new_voter, &proposals_str, &new_voter_secret);
+    testkit.create_block_with_transaction(tx_ballot.clone());
+    assert_eq!(tx_ballot, testkit.find_ballot(&proposals.hash()).unwrap());
+    testkit.assert_participation(&proposals.hash(), 5, 0);
+}
+
+#[test]
+fn test_finalize_unchanged_config_applies_nothing() {
+    use exonum::helpers::Height;
+
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let action = ProposalAction::UpdateConfig {
+        config: BallotConfig::default(),
+    };
+    let proposals = new_action_proposals(5, &action);
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "yes".to_string(),
+    };
+    let tx_votes = validators[..3]
+        .iter()
+        .map(|validator| new_tx_vote(validator, &proposals_hash, &vote_req))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_votes);
+    testkit.create_blocks_until(Height(6));
+    testkit.create_block_with_transaction(new_tx_finalize(&validators[1], &proposals_hash));
+
+    let finalization = BallotSchema::new(testkit.snapshot())
+        .finalizations()
+        .get(&proposals_hash)
+        .unwrap();
+    assert_eq!(Outcome::Winner { proposal_id: 1 }, finalization.outcome);
+    assert!(finalization.quorum_reached);
+    assert_eq!(None, finalization.action);
+}
+
+#[test]
+fn test_finalize_ballot_without_quorum() {
+    use exonum::helpers::Height;
+
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validators = testkit.network().validators().to_vec();
+
+    let action = ProposalAction::RemoveVoter {
+        key: *validators[3].service_keypair().0,
+    };
+    let proposals = new_action_proposals(5, &action);
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "yes".to_string(),
+    };
+    let tx_votes = validators[..2]
+        .iter()
+        .map(|validator| new_tx_vote(validator, &proposals_hash, &vote_req))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_votes);
+
+    testkit.create_blocks_until(Height(6));
+    testkit.create_block_with_transaction(new_tx_finalize(&validators[3], &proposals_hash));
+
+    let snapshot = testkit.snapshot();
+    let schema = BallotSchema::new(&snapshot);
+    let finalization = schema.finalizations().get(&proposals_hash).unwrap();
+    assert_eq!(Outcome::Winner { proposal_id: 1 }, finalization.outcome);
+    assert!(!finalization.quorum_reached);
+    assert_eq!(None, finalization.action);
+    assert!(schema.voters().keys().next().is_none());
+    assert_eq!(4, schema.eligible_voters().len());
+}
+
+fn change_validators(validator_keys: Vec<ValidatorKeys>) -> ProposalAction {
+    ProposalAction::ChangeValidators {
+        validator_keys,
+        activation_delay: MIN_ACTIVATION_DELAY,
+    }
+}
+
+#[test]
+#[cfg(not(feature = "validator-set"))]
+fn test_post_ballot_with_validator_change_unsupported() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validator_keys = testkit.network().validators()[0].public_keys();
+
+    let proposals = new_action_proposals(30, &change_validators(vec![validator_keys]));
+    let tx_ballot = new_tx_ballot(&testkit.network().validators()[0], proposals);
+    testkit.create_block_with_transaction(tx_ballot.clone());
+
+    assert_error_details!(
+        &testkit.snapshot(),
+        &tx_ballot.hash(),
+        ServiceError::InvalidAction {
+            proposal_id: 1,
+            message: "validator set changes need the validator-set feature".to_string(),
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "validator-set")]
+fn test_post_ballot_with_invalid_validator_change() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let validator_keys = testkit.network().validators()[0].public_keys();
+
+    let cases = vec![
+        (change_validators(Vec::new()), "validator set is empty".to_string()),
+        (
+            change_validators(vec![validator_keys, validator_keys]),
+            "validator keys repeat".to_string(),
+        ),
+        (
+            ProposalAction::ChangeValidators {
+                validator_keys: vec![validator_keys],
+                activation_delay: 1,
+            },
+            format!("activation_delay must be at least {}", MIN_ACTIVATION_DELAY),
+        ),
+    ];
+    for (action, message) in cases {
+        let proposals = new_action_proposals(30, &action);
+        let tx_ballot = new_tx_ballot(&testkit.network().validators()[0], proposals);
+        testkit.create_block_with_transaction(tx_ballot.clone());
+
+        assert_error_details!(
+            &testkit.snapshot(),
+            &tx_ballot.hash(),
+            ServiceError::InvalidAction {
+                proposal_id: 1,
+                message,
+            }
+        );
+    }
+}
+
+/// Posts a `ChangeValidators` ballot dropping the last validator, votes for it with the
+/// first three validators and finalizes it.
+#[cfg(feature = "validator-set")]
+fn finalize_validator_change(testkit: &mut TestKit) -> (Hash, StoredConfiguration) {
+    use exonum::helpers::Height;
+
+    let validators = testkit.network().validators().to_vec();
+    let validator_keys = validators[..3]
+        .iter()
+        .map(TestNode::public_keys)
+        .collect::<Vec<_>>();
+    let proposals = new_action_proposals(5, &change_validators(validator_keys.clone()));
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+
+    let vote_req = VoteRequest {
+        proposal_id: 1,
+        proposal_subject: "yes".to_string(),
+    };
+    let tx_votes = validators[..3]
+        .iter()
+        .map(|validator| new_tx_vote(validator, &proposals_hash, &vote_req))
+        .map(|tx| Box::new(tx) as Box<Transaction>)
+        .collect::<Vec<_>>();
+    testkit.create_block_with_transactions(tx_votes);
+
+    testkit.create_blocks_until(Height(6));
+    let actual_config = CoreSchema::new(testkit.snapshot()).actual_configuration();
+    testkit.create_block_with_transaction(new_tx_finalize(&validators[3], &proposals_hash));
+
+    let expected = StoredConfiguration {
+        previous_cfg_hash: actual_config.hash(),
+        actual_from: Height(testkit.height().0 + MIN_ACTIVATION_DELAY),
+        validator_keys,
+        majority_count: None,
+        ..actual_config
+    };
+    (proposals_hash, expected)
+}
+
+#[test]
+#[cfg(feature = "validator-set")]
+fn test_finalize_validator_change() {
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (proposals_hash, expected) = finalize_validator_change(&mut testkit);
+
+    let snapshot = testkit.snapshot();
+    let schema = BallotSchema::new(&snapshot);
+    let finalization = schema.finalizations().get(&proposals_hash).unwrap();
+    assert_eq!(Some(expected.clone()), finalization.validator_config);
+    assert_eq!(
+        Some(proposals_hash),
+        schema.ballots_by_validator_config().get(&expected.hash())
+    );
+}
+
+#[test]
+#[cfg(feature = "validator-set")]
+fn test_expired_validator_change_is_dropped() {
+    use exonum::helpers::Height;
+
+    let mut testkit: TestKit = TestKit::ballot_default();
+    let (_, expired) = finalize_validator_change(&mut testkit);
+    let validators = testkit.network().validators().to_vec();
+
+    let action = ProposalAction::AddVoter {
+        key: crypto::gen_keypair().0,
+    };
+    let proposals = new_action_proposals(expired.actual_from.0 - 1, &action);
+    let proposals_hash = proposals.hash();
+    testkit.create_block_with_transaction(new_tx_ballot(&validators[0], proposals));
+    testkit.create_blocks_until(expired.actual_from);
+    assert!(
+        BallotSchema::new(testkit.snapshot())
+            .ballots_by_validator_config()
+            .contains(&expired.hash())
+    );
+
+    testkit.create_block_with_transaction(new_tx_finalize(&validators[0], &proposals_hash));
+    let snapshot = testkit.snapshot();
+    let schema = BallotSchema::new(&snapshot);
+    assert!(schema.finalizations().contains(&proposals_hash));
+    assert!(!schema.ballots_by_validator_config().contains(&expired.hash()));
+}
+
+#[test]
+#[cfg(feature = "validator-set")]
+fn test_validator_change_reaches_configuration_service() {
+    use exonum_configuration::{Propose, Service as ConfigurationService, Vote as ConfigVote};
+
+    let mut testkit = TestKitBuilder::validator()
+        .with_validators(4)
+        .with_service(ConfigurationService::new())
+        .with_service(BallotService::new())
+        .create();
+    let (_, expected) = finalize_validator_change(&mut testkit);
+
+    // The testkit runs the first validator, which backed the change and proposes it.
+    let (public_key, secret_key) = testkit.network().validators()[0].service_keypair();
+    let propose = Propose::new(
+        public_key,
+        &::serde_json::to_string(&expected).unwrap(),
+        secret_key,
+    );
+    let config_vote = ConfigVote::new(public_key, &expected.hash(), secret_key);
+
+    testkit.create_block();
+    let committed = |testkit: &TestKit, tx_hash: &Hash| {
+        CoreSchema::new(testkit.snapshot())
+            .transaction_results()
+            .get(tx_hash)
+            .map_or(false, |result| result.is_ok())
+    };
+    assert!(committed(&testkit, &propose.hash()));
+    assert!(!committed(&testkit, &config_vote.hash()));
+
+    testkit.create_block();
+    assert!(committed(&testkit, &config_vote.hash()));
+}
+
+#[test]
+fn test_error_code_round_trip() {
+    for code in 0..256 {
+        if let Some(error_code) = ErrorCode::from_u8(code as u8) {
+            assert_eq!(code as u8, error_code as u8);
+        }
+    }
+    assert_eq!(Some(ErrorCode::BallotNoneExists), ErrorCode::from_u8(0));
+    assert_eq!(Some(ErrorCode::InvalidAttachment), ErrorCode::from_u8(41));
+    assert_eq!(Some(ErrorCode::InternalError), ErrorCode::from_u8(255));
+    assert_eq!(None, ErrorCode::from_u8(254));
+}
+
+#[test]
+fn test_error_description_round_trip() {
+    let errors = vec![
+        ServiceError::BallotNoneExists {
+            proposals_hash: hash(&[1]),
+        },
+        ServiceError::NoProposals,
+        ServiceError::VotedProposalNoneExists {
+            proposal_id: 2,
+            proposal_subject: "geralt of rivia doesn't exist".to_string(),
+        },
+        ServiceError::InvalidProposals {
+            message: "expected `,` at line 1 column 2: trailing".to_string(),
+            line: 1,
+            column: 2,
+        },
+        ServiceError::BallotAlreadyClosed {
+            deadline: 30,
+            height: 31,
+        },
+    ];
+
+    for error in errors {
+        let tx_error = ExecutionError::from(error.clone());
+        let description = tx_error.description().unwrap();
+        assert_eq!(Some(error), ServiceError::from_description(description));
+    }
+    let tx_error = ExecutionError::from(ServiceError::BallotAlreadyClosed {
+        deadline: 30,
+        height: 31,
+    });
+    assert_eq!(
+        Some(r#"{"error":"ballot_already_closed","deadline":30,"height":31}"#),
+        tx_error.description()
+    );
+    assert_eq!(
+        None,
+        ServiceError::from_description("Ballot already closed, deadline 30 height 31")
+    );
 }
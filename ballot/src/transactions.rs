@@ -1,10 +1,14 @@
-use exonum::blockchain::{ExecutionResult, Schema as CoreSchema, Transaction};
+use exonum::blockchain::{ExecutionResult, Schema as CoreSchema, StoredConfiguration, Transaction};
 use exonum::crypto::{CryptoHash, Hash, PublicKey};
+use exonum::helpers::Height;
 use exonum::messages::Message;
 use exonum::storage::{Fork, Snapshot};
 
 use SERVICE_ID;
-use schema::{BallotData, MaybeVote, ProposalList, Schema};
+use schema::{answer_sheet_key, question_tally_key, Amendment, Answer, AnswerSheet, BallotConfig,
+             BallotData, BallotLimits, ConfigProposal, Electorate, Finalization, MaybeVote,
+             Outcome, Proposal, ProposalAction, ProposalList, QuestionOutcome, Reference,
+             Revision, Schema, Sponsorship, Tally, VoteRecord, VotingMode};
 use error::Error as ServiceError;
 
 fn validator_id(snapshot: &Snapshot, key: &PublicKey) -> Option<usize> {
@@ -13,6 +17,421 @@ fn validator_id(snapshot: &Snapshot, key: &PublicKey) -> Option<usize> {
     keys.iter().position(|k| k.service_key == *key)
 }
 
+fn check_limits(proposals: &ProposalList, limits: &BallotLimits) -> Result<(), ServiceError> {
+    if proposals.has_questions() {
+        check_questions(proposals, limits)
+    } else {
+        check_proposals(proposals.proposals(), limits)
+    }
+}
+
+/// Longest proposals json of a ballot, checked before parsing it. Proposals within
+/// the default `BallotLimits` fit in it.
+pub const MAX_PROPOSALS_LEN: usize = 1 << 19;
+
+/// Longest amendment json, checked before parsing it. An amendment carries one
+/// proposal, which fits in it within the default `BallotLimits`.
+pub const MAX_AMENDMENT_LEN: usize = 1 << 14;
+
+/// Most attachments a proposal may have.
+pub const MAX_ATTACHMENTS: usize = 8;
+
+/// Longest mime type of an attachment, parameters included.
+pub const MAX_MIME_TYPE_LEN: usize = 255;
+
+fn check_proposals(proposals: &[Proposal], limits: &BallotLimits) -> Result<(), ServiceError> {
+    use self::ServiceError::*;
+
+    let count = proposals.len() as u64;
+    if count == 0 {
+        Err(NoProposals)?
+    }
+    if count > u64::from(limits.max_proposals) {
+        Err(TooManyProposals {
+            count,
+            max: limits.max_proposals,
+        })?
+    }
+
+    for proposal in proposals {
+        if proposal.subject().trim().is_empty() {
+            Err(EmptySubject {
+                proposal_id: proposal.id(),
+            })?
+        }
+        if proposal.subject().len() as u64 > u64::from(limits.max_subject_len) {
+            Err(SubjectTooLong {
+                proposal_id: proposal.id(),
+                len: proposal.subject().len() as u64,
+                max: limits.max_subject_len,
+            })?
+        }
+        if proposal.description().len() as u64 > u64::from(limits.max_description_len) {
+            Err(DescriptionTooLong {
+                proposal_id: proposal.id(),
+                len: proposal.description().len() as u64,
+                max: limits.max_description_len,
+            })?
+        }
+        check_attachments(proposal, limits)?;
+    }
+
+    Ok(())
+}
+
+/// Mime types are checked for a `type/subtype` shape only, parameters such as
+/// `charset` are allowed.
+fn check_attachments(proposal: &Proposal, limits: &BallotLimits) -> Result<(), ServiceError> {
+    let invalid = |message: String| ServiceError::InvalidAttachment {
+        proposal_id: proposal.id(),
+        message,
+    };
+
+    let attachments = proposal.attachments();
+    if attachments.len() > MAX_ATTACHMENTS {
+        Err(invalid(format!("more than {} attachments", MAX_ATTACHMENTS)))?
+    }
+    for (i, attachment) in attachments.iter().enumerate() {
+        if attachment.size == 0 {
+            Err(invalid(format!("attachment {} is empty", attachment.hash)))?
+        }
+        if attachment.size > limits.max_attachment_size {
+            Err(invalid(format!(
+                "attachment {} is larger than {} bytes",
+                attachment.hash, limits.max_attachment_size
+            )))?
+        }
+        let mime_type = &attachment.mime_type;
+        let essence = mime_type.split(';').next().unwrap_or_default();
+        let shaped = match essence.find('/') {
+            Some(pos) => pos > 0 && pos + 1 < essence.len(),
+            None => false,
+        };
+        let valid = shaped && !essence.contains(char::is_whitespace)
+            && mime_type.len() <= MAX_MIME_TYPE_LEN;
+        if !valid {
+            Err(invalid(format!("{:?} isn't a valid mime type", mime_type)))?
+        }
+        if attachments[..i].iter().any(|other| other.hash == attachment.hash) {
+            Err(invalid(format!("attachment {} repeats", attachment.hash)))?
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks each question like the proposals of a ballot without questions, except that
+/// `max_proposals` caps the proposals of all questions together. Discussion,
+/// nominations and actions only apply to the proposals of the list, which has to be
+/// empty.
+fn check_questions(proposals: &ProposalList, limits: &BallotLimits) -> Result<(), ServiceError> {
+    use self::ServiceError::*;
+
+    let invalid = |message: String| InvalidQuestions { message };
+    if !proposals.proposals().is_empty() {
+        Err(invalid("proposals of a ballot with questions go into the questions".into()))?
+    }
+    if proposals.discussion_until().is_some() || proposals.nominations_until().is_some() {
+        Err(invalid("ballots with questions can't be discussed or nominated to".into()))?
+    }
+
+    let questions = proposals.questions();
+    let count = questions
+        .iter()
+        .map(|question| question.proposals().len() as u64)
+        .sum::<u64>();
+    if count > u64::from(limits.max_proposals) {
+        Err(TooManyProposals {
+            count,
+            max: limits.max_proposals,
+        })?
+    }
+
+    for (i, question) in questions.iter().enumerate() {
+        if questions[..i].iter().any(|other| other.id() == question.id()) {
+            Err(invalid(format!("question id {} repeats", question.id())))?
+        }
+        if question.subject().trim().is_empty() {
+            Err(invalid(format!("question {} has an empty subject", question.id())))?
+        }
+        if question.subject().len() as u64 > u64::from(limits.max_subject_len) {
+            Err(invalid(format!(
+                "subject of question {} is longer than {}",
+                question.id(),
+                limits.max_subject_len
+            )))?
+        }
+        if let VotingMode::Approval { max_choices: 0 } = question.mode() {
+            Err(invalid(format!("question {} allows no choices", question.id())))?
+        }
+
+        check_proposals(question.proposals(), limits)?;
+        if let Some(proposal_id) = question.duplicate_id() {
+            Err(PostDuplicateProposalId { proposal_id })?
+        }
+        if question.proposals().iter().any(|proposal| proposal.action().is_some()) {
+            Err(invalid(format!(
+                "proposals of question {} can't have actions",
+                question.id()
+            )))?
+        }
+    }
+
+    Ok(())
+}
+
+/// Most tags a ballot may have.
+pub const MAX_TAGS: usize = 16;
+
+/// Categories and tags go into api query strings, so they are limited to lowercase
+/// ascii letters, digits, `-` and `_`.
+fn is_label(label: &str) -> bool {
+    !label.is_empty()
+        && label
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Checks the metadata of a ballot, lengths against the subject and description limits.
+fn check_metadata(
+    snapshot: &Snapshot,
+    proposals: &ProposalList,
+    limits: &BallotLimits,
+) -> Result<(), ServiceError> {
+    use self::ServiceError::*;
+
+    let invalid = |message: String| InvalidMetadata { message };
+    let max_label_len = limits.max_subject_len as usize;
+    let max_text_len = limits.max_description_len as usize;
+
+    if let Some(title) = proposals.title() {
+        if title.trim().is_empty() || title.len() > max_label_len {
+            Err(invalid(format!("title has to be 1 to {} bytes long", max_label_len)))?
+        }
+    }
+    if let Some(description) = proposals.description() {
+        if description.len() > max_text_len {
+            Err(invalid(format!("description is longer than {}", max_text_len)))?
+        }
+    }
+
+    let labels = proposals
+        .category()
+        .into_iter()
+        .chain(proposals.tags().iter().map(String::as_str));
+    for label in labels {
+        if !is_label(label) || label.len() > max_label_len {
+            Err(invalid(format!("{:?} isn't a valid category or tag", label)))?
+        }
+    }
+    let tags = proposals.tags();
+    if tags.len() > MAX_TAGS {
+        Err(invalid(format!("more than {} tags", MAX_TAGS)))?
+    }
+    for (i, tag) in tags.iter().enumerate() {
+        if tags[..i].contains(tag) {
+            Err(invalid(format!("tag {} repeats", tag)))?
+        }
+    }
+
+    if let Some(&Reference::Url { ref url }) = proposals.reference() {
+        let scheme = url.starts_with("https://") || url.starts_with("http://");
+        if !scheme || url.len() > max_text_len {
+            Err(invalid(format!(
+                "reference url has to start with http:// or https:// and be at most {} \
+                 bytes long",
+                max_text_len
+            )))?
+        }
+    }
+
+    if let Some(parent) = proposals.parent() {
+        if !Schema::new(snapshot)
+            .ballot_data_by_proposals_hash()
+            .contains(parent)
+        {
+            Err(ParentBallotNoneExists { parent: *parent })?
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the ballot accepts votes in the block after `latest_height`.
+fn check_accepts_votes<T: AsRef<Snapshot>>(
+    schema: &Schema<T>,
+    proposals_hash: &Hash,
+    proposals: &ProposalList,
+    latest_height: Height,
+) -> Result<(), ServiceError> {
+    use self::ServiceError::*;
+
+    if latest_height > Height(proposals.deadline()) {
+        Err(BallotAlreadyClosed {
+            deadline: proposals.deadline(),
+            height: latest_height.0,
+        })?
+    }
+
+    if proposals.in_nominations(latest_height) {
+        Err(BallotNominating {
+            nominations_until: proposals.nominations_until().unwrap_or_default(),
+            height: latest_height.0,
+        })?
+    }
+    if proposals.in_discussion(latest_height) {
+        Err(BallotInDiscussion {
+            discussion_until: proposals.discussion_until().unwrap_or_default(),
+            height: latest_height.0,
+        })?
+    }
+
+    if let Some(sponsorship) = schema.sponsorships().get(proposals_hash) {
+        if !sponsorship.is_open() {
+            Err(BallotSeekingSponsors {
+                proposals_hash: *proposals_hash,
+                sponsors: sponsorship.sponsors.len() as u64,
+                min_sponsors: sponsorship.min_sponsors,
+            })?
+        }
+    }
+
+    Ok(())
+}
+
+fn check_deadline(
+    proposals: &ProposalList,
+    limits: &BallotLimits,
+    latest_height: Height,
+) -> Result<(), ServiceError> {
+    use self::ServiceError::*;
+
+    let height = latest_height.0;
+    let min_deadline = height.saturating_add(limits.min_deadline_distance);
+    let max_deadline = height.saturating_add(limits.max_deadline_distance);
+    if proposals.deadline() < min_deadline {
+        Err(DeadlineTooSoon {
+            deadline: proposals.deadline(),
+            height,
+            min_deadline,
+        })?
+    }
+    if proposals.deadline() > max_deadline {
+        Err(DeadlineTooFar {
+            deadline: proposals.deadline(),
+            height,
+            max_deadline,
+        })?
+    }
+
+    if let Some(discussion_until) = proposals.discussion_until() {
+        if discussion_until <= height || discussion_until >= proposals.deadline() {
+            Err(DiscussionOutOfRange {
+                discussion_until,
+                height,
+                deadline: proposals.deadline(),
+            })?
+        }
+    }
+    if let Some(nominations_until) = proposals.nominations_until() {
+        if nominations_until <= height || nominations_until >= proposals.deadline() {
+            Err(NominationsOutOfRange {
+                nominations_until,
+                height,
+                deadline: proposals.deadline(),
+            })?
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the ballots of `author` against `max_open_ballots` and `min_ballot_interval`,
+/// for a ballot in the block after `latest_height`.
+fn check_rate(
+    snapshot: &Snapshot,
+    author: &PublicKey,
+    limits: &BallotLimits,
+    latest_height: Height,
+) -> Result<(), ServiceError> {
+    use self::ServiceError::*;
+
+    let schema = Schema::new(snapshot);
+    let open = schema.open_ballots_by_author(author, latest_height);
+    if open >= u64::from(limits.max_open_ballots) {
+        Err(TooManyOpenBallots {
+            open,
+            max: limits.max_open_ballots,
+        })?
+    }
+
+    let height = latest_height.next().0;
+    if let Some(last_height) = schema.last_ballot_heights().get(author) {
+        if height < last_height.saturating_add(limits.min_ballot_interval) {
+            Err(BallotIntervalTooShort {
+                height,
+                last_height,
+                min_interval: limits.min_ballot_interval,
+            })?
+        }
+    }
+
+    Ok(())
+}
+
+/// Blocks between finalizing a `ChangeValidators` ballot and the new validator set
+/// taking over, leaves time for the configuration service propose and its votes.
+pub const MIN_ACTIVATION_DELAY: u64 = 10;
+
+fn check_actions(proposals: &ProposalList) -> Result<(), ServiceError> {
+    for proposal in proposals.proposals() {
+        let checked = match proposal.action() {
+            Some(&ProposalAction::UpdateConfig { ref config }) => config.validate(),
+            Some(&ProposalAction::ChangeValidators { .. }) if !cfg!(feature = "validator-set") => {
+                Err("validator set changes need the validator-set feature".to_string())
+            }
+            Some(&ProposalAction::ChangeValidators {
+                ref validator_keys,
+                activation_delay,
+            }) => {
+                let mut service_keys = validator_keys
+                    .iter()
+                    .map(|keys| keys.service_key)
+                    .collect::<Vec<_>>();
+                let mut consensus_keys = validator_keys
+                    .iter()
+                    .map(|keys| keys.consensus_key)
+                    .collect::<Vec<_>>();
+                service_keys.sort();
+                service_keys.dedup();
+                consensus_keys.sort();
+                consensus_keys.dedup();
+
+                if validator_keys.is_empty() {
+                    Err("validator set is empty".to_string())
+                } else if service_keys.len() != validator_keys.len()
+                    || consensus_keys.len() != validator_keys.len()
+                {
+                    Err("validator keys repeat".to_string())
+                } else if activation_delay < MIN_ACTIVATION_DELAY {
+                    Err(format!(
+                        "activation_delay must be at least {}",
+                        MIN_ACTIVATION_DELAY
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        };
+        checked.map_err(|message| ServiceError::InvalidAction {
+            proposal_id: proposal.id(),
+            message,
+        })?;
+    }
+    Ok(())
+}
+
 transactions!{
     pub Transactions {
         const SERVICE_ID = SERVICE_ID;
@@ -29,6 +448,48 @@ transactions!{
             proposal_id: u64,
             proposal_subject: &str,
         }
+
+        struct ProposeConfig {
+            from: &PublicKey,
+            /// `BallotConfig` json string
+            config: &str,
+        }
+
+        struct VoteConfig {
+            from: &PublicKey,
+            config_hash: &Hash,
+        }
+
+        struct FinalizeBallot {
+            from: &PublicKey,
+            proposals_hash: &Hash,
+        }
+
+        struct SponsorBallot {
+            from: &PublicKey,
+            proposals_hash: &Hash,
+        }
+
+        struct AmendBallot {
+            from: &PublicKey,
+            proposals_hash: &Hash,
+            /// `Amendment` json string
+            amendment: &str,
+        }
+
+        struct Nominate {
+            from: &PublicKey,
+            proposals_hash: &Hash,
+            subject: &str,
+            description: &str,
+        }
+
+        struct VoteQuestions {
+            from: &PublicKey,
+            proposals_hash: &Hash,
+            /// `Answer` list json string
+            answers: &str,
+        }
     }
 }
 
@@ -36,23 +497,49 @@ impl Ballot {
     fn precheck(&self, snapshot: &Snapshot) -> Result<ProposalList, ServiceError> {
         use self::ServiceError::*;
 
-        if validator_id(snapshot, self.from()).is_none() {
-            Err(UnknownSender)?
+        if !Schema::new(snapshot).is_voter(self.from()) {
+            Err(UnknownSender {
+                sender: *self.from(),
+            })?
         }
 
+        if self.proposals().len() > MAX_PROPOSALS_LEN {
+            Err(ProposalsTooLong {
+                len: self.proposals().len() as u64,
+                max: MAX_PROPOSALS_LEN as u64,
+            })?
+        }
         let proposals: ProposalList = ProposalList::try_deserialize(self.proposals().as_bytes())
-            .map_err(|e| InvalidProposals(e))?;
+            .map_err(ServiceError::invalid_proposals)?;
+
+        let latest_height = CoreSchema::new(snapshot).height();
+        let limits = Schema::new(snapshot).limits();
+        check_limits(&proposals, &limits)?;
+        check_metadata(snapshot, &proposals, &limits)?;
+        check_deadline(&proposals, &limits, latest_height)?;
+        check_actions(&proposals)?;
+        check_rate(snapshot, self.from(), &limits, latest_height)?;
+
+        // The author backs the ballot by posting it and can't sponsor it.
+        let eligible = Schema::new(snapshot).eligible_voters().len().saturating_sub(1) as u64;
+        if u64::from(proposals.min_sponsors()) > eligible {
+            Err(SponsorsUnreachable {
+                min_sponsors: proposals.min_sponsors(),
+                eligible,
+            })?
+        }
 
-        if proposals.has_duplicate_id() {
-            Err(PostDuplicateProposalId)?
+        if let Some(proposal_id) = proposals.duplicate_id() {
+            Err(PostDuplicateProposalId { proposal_id })?
         }
 
+        let proposals_hash = proposals.hash();
         if Schema::new(snapshot)
             .ballot_data_by_proposals_hash()
-            .get(&proposals.hash())
+            .get(&proposals_hash)
             .is_some()
         {
-            Err(BallotAlreadyPosted)?
+            Err(BallotAlreadyPosted { proposals_hash })?
         }
 
         Ok(proposals)
@@ -60,35 +547,81 @@ impl Ballot {
 
     fn save(&self, view: &mut Fork, proposals: ProposalList) {
         let proposals_hash = proposals.hash();
-        let num_validators = {
-            let core_config = CoreSchema::new(view.as_ref()).actual_configuration();
-            core_config.validator_keys.len()
-        };
-
+        let latest_height = CoreSchema::new(view.as_ref()).height();
+        let height = latest_height.next();
         let mut schema = Schema::new(view);
+        let electorate = Electorate::new(schema.eligible_voters());
 
         let ballot_data = {
             let mut votes_table = schema.votes_by_proposals_hash_mut(&proposals_hash);
             debug_assert!(votes_table.is_empty());
 
-            for _ in 0..num_validators {
+            for _ in 0..electorate.len() {
                 votes_table.push(MaybeVote::none());
             }
 
             BallotData::new(
                 self.clone(),
                 &votes_table.merkle_root(),
-                num_validators as u64,
+                electorate.len() as u32,
+                0,
             )
         };
 
         schema
             .ballot_data_by_proposals_hash_mut()
             .put(&proposals_hash, ballot_data);
+        schema
+            .tallies_mut()
+            .put(&proposals_hash, Tally::new(&proposals));
+        for question in proposals.questions() {
+            schema.tallies_mut().put(
+                &question_tally_key(&proposals_hash, question.id()),
+                Tally::for_question(question),
+            );
+        }
+        schema.electorates_mut().put(&proposals_hash, electorate);
+        if proposals.min_sponsors() > 0 {
+            schema
+                .sponsorships_mut()
+                .put(&proposals_hash, Sponsorship::new(proposals.min_sponsors()));
+        }
+
+        let ordinal = schema.proposals_hash_by_ordinal().len();
         schema.proposals_hash_by_ordinal_mut().push(proposals_hash);
+        index_ballot(&mut schema, ordinal, self.from(), &proposals);
+        schema
+            .last_ballot_heights_mut()
+            .put(self.from(), height.0);
+        let mut open = schema.open_ballots().get(self.from()).unwrap_or_default();
+        open.push(proposals.deadline(), latest_height);
+        schema.open_ballots_mut().put(self.from(), open);
     }
 }
 
+/// Adds the ballot at `ordinal` to the indexes by deadline, author, category, tag and
+/// attachment. None of them is part of `state_hash`, `migrations::index_ballots` fills
+/// them for ballots posted before they existed.
+pub(crate) fn index_ballot(
+    schema: &mut Schema<&mut Fork>,
+    ordinal: u64,
+    author: &PublicKey,
+    proposals: &ProposalList,
+) {
+    schema.deadlines_mut().insert(proposals.deadline());
+    schema
+        .ordinals_by_deadline_mut(proposals.deadline())
+        .push(ordinal);
+    schema.ordinals_by_author_mut(author).push(ordinal);
+    if let Some(category) = proposals.category() {
+        schema.ordinals_by_category_mut(category).push(ordinal);
+    }
+    for tag in proposals.tags() {
+        schema.ordinals_by_tag_mut(tag).push(ordinal);
+    }
+    index_attachments(schema, proposals);
+}
+
 impl Transaction for Ballot {
     fn verify(&self) -> bool {
         self.verify_signature(self.from())
@@ -103,76 +636,896 @@ impl Transaction for Ballot {
 
 impl Vote {
     fn precheck(&self, snapshot: &Snapshot) -> Result<(BallotData, usize), ServiceError> {
-        use exonum::blockchain::Schema as CoreSchema;
-        use exonum::helpers::Height;
         use self::ServiceError::*;
 
-        let validator_id = validator_id(snapshot, self.from()).ok_or(UnknownSender)?;
         let schema = Schema::new(snapshot);
 
         let ballot_data = schema
             .ballot_data_by_proposals_hash()
             .get(self.proposals_hash())
-            .ok_or(BallotNoneExists)?;
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+
+        let position = schema
+            .electorate(self.proposals_hash())
+            .and_then(|electorate| electorate.position(self.from()))
+            .ok_or_else(|| UnknownSender {
+                sender: *self.from(),
+            })?;
 
         let vote = schema
             .votes_by_proposals_hash(self.proposals_hash())
-            .get(validator_id as u64);
+            .get(position as u64);
         if let Some(vote) = vote {
             if vote.is_some() {
-                Err(AlreadyVoted)?
+                Err(AlreadyVoted {
+                    voter: *self.from(),
+                })?
             }
         } else {
-            Err(InternalError(format!(
-                "Vote position isn't reserve. Sender: {}",
-                self.from()
-            )))?;
+            Err(VotePositionNotReserved {
+                validator_id: position as u64,
+                sender: *self.from(),
+            })?;
         }
 
-        let proposals = ProposalList::try_deserialize(
-            ballot_data.tx_ballot().proposals().as_bytes(),
-        ).map_err(|e| InvalidProposals(e))?;
+        // Votes go to the latest revision, which is frozen once the discussion ends.
+        let proposals = match schema.proposals(self.proposals_hash()) {
+            Some(proposals) => proposals,
+            None => ProposalList::try_deserialize(ballot_data.tx_ballot().proposals().as_bytes())
+                .map_err(ServiceError::invalid_proposals)?,
+        };
         if !proposals.contains(self.proposal_id(), self.proposal_subject()) {
-            Err(VotedProposalNoneExists)?
+            Err(VotedProposalNoneExists {
+                proposal_id: self.proposal_id(),
+                proposal_subject: self.proposal_subject().to_string(),
+            })?
         }
 
-        let core_schema = CoreSchema::new(snapshot);
-        let latest_height = core_schema.height();
-        if latest_height > Height(proposals.deadline()) {
-            Err(BallotAlreadyClosed)?
-        }
+        let latest_height = CoreSchema::new(snapshot).height();
+        check_accepts_votes(&schema, self.proposals_hash(), &proposals, latest_height)?;
 
-        Ok((ballot_data, validator_id))
+        Ok((ballot_data, position))
     }
 
-    fn save(&self, view: &mut Fork, ballot_data: BallotData, validator_id: u64) {
+    fn save(&self, view: &mut Fork, ballot_data: BallotData, position: u64) {
+        // The block being executed isn't committed yet, its height is next to the latest.
+        let height = CoreSchema::new(view.as_ref()).height().next();
         let mut schema = Schema::new(view);
 
-        let ballot_data = {
+        let (votes_history_hash, electorate_size) = {
             let mut votes_table = schema.votes_by_proposals_hash_mut(self.proposals_hash());
-            votes_table.set(validator_id, MaybeVote::some(self.clone()));
+            votes_table.set(position, MaybeVote::some(self.clone()));
+            (votes_table.merkle_root(), votes_table.len() as u32)
+        };
 
-            BallotData::new(
-                ballot_data.tx_ballot(),
-                &votes_table.merkle_root(),
-                validator_id,
+        let tally = match schema.tallies().get(self.proposals_hash()) {
+            Some(mut tally) => {
+                tally.add_vote(self.proposal_id());
+                tally
+            }
+            // Ballots posted before tallies were stored, the vote is already in the list.
+            None => {
+                let proposals = schema.proposals(self.proposals_hash()).unwrap();
+                Tally::from_votes(&proposals, &schema.votes(self.proposals_hash()))
+            }
+        };
+        // Participation is recounted rather than incremented, which also repairs the
+        // `num_voters` of ballots stored by older versions of the service.
+        let ballot_data = BallotData::new(
+            ballot_data.tx_ballot(),
+            &votes_history_hash,
+            electorate_size,
+            tally.votes_cast() as u32,
+        );
+        schema
+            .ballot_data_by_proposals_hash_mut()
+            .put(self.proposals_hash(), ballot_data);
+        schema.tallies_mut().put(self.proposals_hash(), tally);
+        schema
+            .votes_by_voter_mut(self.from())
+            .push(VoteRecord::new(self.clone(), height.0));
+    }
+}
+
+impl Transaction for Vote {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let (ballot_data, position) = self.precheck(view.as_ref())?;
+        self.save(view, ballot_data, position as u64);
+        Ok(())
+    }
+}
+
+/// Records the approval of `from` and applies the proposal once approvals of current
+/// validators exceed the governance threshold of the configuration in effect.
+fn approve_config(
+    view: &mut Fork,
+    config_hash: &Hash,
+    mut proposal: ConfigProposal,
+    from: &PublicKey,
+) {
+    let (validator_keys, height) = {
+        let core_schema = CoreSchema::new(view.as_ref());
+        let validator_keys = core_schema
+            .actual_configuration()
+            .validator_keys
+            .iter()
+            .map(|keys| keys.service_key)
+            .collect::<Vec<_>>();
+        (validator_keys, core_schema.height().next())
+    };
+    proposal.approvals.push(*from);
+
+    let mut schema = Schema::new(view);
+    let approvals = proposal
+        .approvals
+        .iter()
+        .filter(|key| validator_keys.contains(key))
+        .count() as u64;
+    let required = schema
+        .config()
+        .governance
+        .required(validator_keys.len() as u64);
+    if approvals >= required {
+        schema.config_mut().set(proposal.config.clone());
+        proposal.applied_at = Some(height.0);
+    }
+    schema.config_proposals_mut().put(config_hash, proposal);
+}
+
+impl ProposeConfig {
+    fn precheck(&self, snapshot: &Snapshot) -> Result<BallotConfig, ServiceError> {
+        use self::ServiceError::*;
+
+        if validator_id(snapshot, self.from()).is_none() {
+            Err(UnknownSender {
+                sender: *self.from(),
+            })?
+        }
+
+        let config = BallotConfig::try_deserialize(self.config().as_bytes())
+            .map_err(|e| InvalidConfig {
+                message: e.to_string(),
+            })?;
+        config
+            .validate()
+            .map_err(|message| InvalidConfig { message })?;
+
+        let config_hash = config.hash();
+        let schema = Schema::new(snapshot);
+        if let Some(proposal) = schema.config_proposals().get(&config_hash) {
+            match proposal.applied_at {
+                None => Err(ConfigAlreadyProposed { config_hash })?,
+                // A superseded config may be proposed again.
+                Some(height) if schema.config().hash() == config_hash => {
+                    Err(ConfigAlreadyApplied {
+                        config_hash,
+                        height,
+                    })?
+                }
+                Some(_) => (),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+impl Transaction for ProposeConfig {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let config = self.precheck(view.as_ref())?;
+        let proposal = ConfigProposal {
+            config: config.clone(),
+            approvals: Vec::new(),
+            applied_at: None,
+        };
+        approve_config(view, &config.hash(), proposal, self.from());
+        Ok(())
+    }
+}
+
+impl VoteConfig {
+    fn precheck(&self, snapshot: &Snapshot) -> Result<ConfigProposal, ServiceError> {
+        use self::ServiceError::*;
+
+        if validator_id(snapshot, self.from()).is_none() {
+            Err(UnknownSender {
+                sender: *self.from(),
+            })?
+        }
+
+        let proposal = Schema::new(snapshot)
+            .config_proposals()
+            .get(self.config_hash())
+            .ok_or_else(|| ConfigProposalNoneExists {
+                config_hash: *self.config_hash(),
+            })?;
+        if let Some(height) = proposal.applied_at {
+            Err(ConfigAlreadyApplied {
+                config_hash: *self.config_hash(),
+                height,
+            })?
+        }
+        if proposal.approvals.contains(self.from()) {
+            Err(AlreadyVoted {
+                voter: *self.from(),
+            })?
+        }
+
+        Ok(proposal)
+    }
+}
+
+impl Transaction for VoteConfig {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let proposal = self.precheck(view.as_ref())?;
+        approve_config(view, self.config_hash(), proposal, self.from());
+        Ok(())
+    }
+}
+
+/// Applies the winning proposal's action, returns it if it changed anything.
+///
+/// The registry starts as a copy of the validators on the first voter action, so
+/// that adding a voter doesn't take the vote away from them.
+fn apply_action(
+    schema: &mut Schema<&mut Fork>,
+    action: ProposalAction,
+    height: Height,
+) -> Option<ProposalAction> {
+    match action {
+        ProposalAction::UpdateConfig { ref config } => {
+            if schema.config() == *config {
+                return None;
+            }
+            schema.config_mut().set(config.clone());
+        }
+        ProposalAction::AddVoter { ref key } | ProposalAction::RemoveVoter { ref key } => {
+            let voters = schema.eligible_voters();
+            let adding = match action {
+                ProposalAction::AddVoter { .. } => true,
+                _ => false,
+            };
+            // Removing the last voter would leave nobody to post or vote on ballots.
+            if adding == voters.contains(key) || (!adding && voters.len() == 1) {
+                return None;
+            }
+
+            let mut registry = schema.voters_mut();
+            if registry.keys().next().is_none() {
+                for voter in &voters {
+                    registry.put(voter, height.0);
+                }
+            }
+            if adding {
+                registry.put(key, height.0);
+            } else {
+                registry.remove(key);
+            }
+        }
+        // Handed over to the configuration service, see `FinalizeBallot::save`.
+        ProposalAction::ChangeValidators { .. } => (),
+    }
+    Some(action)
+}
+
+/// Rewrites the participation counts of a ballot from its vote list and tally, like
+/// `Vote::save` does. Finalizing repairs the `num_voters` of legacy ballots that
+/// closed without another vote.
+/// Drops validator configurations due by `height` from `ballot.ballots_by_validator_config`,
+/// the configuration service no longer takes proposes or votes for them.
+fn drop_expired_validator_configs(schema: &mut Schema<&mut Fork>, height: Height) {
+    let expired = schema
+        .ballots_by_validator_config()
+        .iter()
+        .filter(|&(_, ref proposals_hash)| {
+            schema
+                .finalizations()
+                .get(proposals_hash)
+                .and_then(|finalization| finalization.validator_config)
+                .map_or(true, |config| config.actual_from <= height)
+        })
+        .map(|(config_hash, _)| config_hash)
+        .collect::<Vec<_>>();
+    for config_hash in expired {
+        schema.ballots_by_validator_config_mut().remove(&config_hash);
+    }
+}
+
+fn recount_participation(schema: &mut Schema<&mut Fork>, proposals_hash: &Hash, tally: &Tally) {
+    let ballot_data = match schema.ballot_data_by_proposals_hash().get(proposals_hash) {
+        Some(ballot_data) => ballot_data,
+        None => return,
+    };
+    let electorate_size = schema.votes_by_proposals_hash(proposals_hash).len() as u32;
+    let ballot_data = BallotData::new(
+        ballot_data.tx_ballot(),
+        ballot_data.votes_history_hash(),
+        electorate_size,
+        tally.votes_cast() as u32,
+    );
+    schema
+        .ballot_data_by_proposals_hash_mut()
+        .put(proposals_hash, ballot_data);
+}
+
+impl FinalizeBallot {
+    fn precheck(&self, snapshot: &Snapshot) -> Result<ProposalList, ServiceError> {
+        use self::ServiceError::*;
+
+        let schema = Schema::new(snapshot);
+        if !schema.is_voter(self.from()) {
+            Err(UnknownSender {
+                sender: *self.from(),
+            })?
+        }
+
+        let proposals = schema
+            .proposals(self.proposals_hash())
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+
+        let latest_height = CoreSchema::new(snapshot).height();
+        if latest_height <= Height(proposals.deadline()) {
+            Err(BallotNotClosed {
+                deadline: proposals.deadline(),
+                height: latest_height.0,
+            })?
+        }
+
+        if let Some(finalization) = schema.finalizations().get(self.proposals_hash()) {
+            Err(BallotAlreadyFinalized {
+                proposals_hash: *self.proposals_hash(),
+                height: finalization.height,
+            })?
+        }
+
+        Ok(proposals)
+    }
+
+    fn save(&self, view: &mut Fork, proposals: ProposalList) {
+        let (height, actual_config, config_scheduled) = {
+            let core_schema = CoreSchema::new(view.as_ref());
+            (
+                core_schema.height().next(),
+                core_schema.actual_configuration(),
+                core_schema.following_configuration().is_some(),
             )
         };
+        let mut schema = Schema::new(view);
+
+        let tally = schema
+            .tallies()
+            .get(self.proposals_hash())
+            .unwrap_or_else(|| Tally::from_votes(&proposals, &schema.votes(self.proposals_hash())));
+        let electorate_size = schema
+            .electorate(self.proposals_hash())
+            .map_or(0, |electorate| electorate.len() as u32);
+        let answered = schema
+            .ballot_data_by_proposals_hash()
+            .get(self.proposals_hash())
+            .map_or(0, |ballot_data| ballot_data.votes_cast());
+        // Each answer sheet counts once, however many proposals it chose.
+        let votes_cast = if proposals.has_questions() {
+            u64::from(answered)
+        } else {
+            tally.votes_cast()
+        };
+        let quorum = schema.config().quorum.required(u64::from(electorate_size));
+        let quorum_reached = votes_cast >= quorum;
+        if !proposals.has_questions() {
+            recount_participation(&mut schema, self.proposals_hash(), &tally);
+        }
+        let questions = proposals
+            .questions()
+            .iter()
+            .map(|question| QuestionOutcome {
+                question_id: question.id(),
+                outcome: schema
+                    .tallies()
+                    .get(&question_tally_key(self.proposals_hash(), question.id()))
+                    .unwrap_or_else(|| Tally::for_question(question))
+                    .outcome(),
+            })
+            .collect();
+
+        let outcome = tally.outcome();
+        let action = match outcome {
+            Outcome::Winner { proposal_id } if quorum_reached => proposals
+                .proposal(proposal_id)
+                .and_then(|proposal| proposal.action().cloned()),
+            _ => None,
+        };
+        let action = match action {
+            // The configuration service keeps a single scheduled configuration.
+            Some(ProposalAction::ChangeValidators { .. }) if config_scheduled => None,
+            action => action.and_then(|action| apply_action(&mut schema, action, height)),
+        };
+
+        drop_expired_validator_configs(&mut schema, height);
+        let validator_config = match action {
+            Some(ProposalAction::ChangeValidators {
+                ref validator_keys,
+                activation_delay,
+            }) => {
+                let config = StoredConfiguration {
+                    previous_cfg_hash: actual_config.hash(),
+                    actual_from: Height(height.0.saturating_add(activation_delay)),
+                    validator_keys: validator_keys.clone(),
+                    majority_count: None,
+                    ..actual_config
+                };
+                schema
+                    .ballots_by_validator_config_mut()
+                    .put(&config.hash(), *self.proposals_hash());
+                Some(config)
+            }
+            _ => None,
+        };
 
+        let finalization = Finalization {
+            height: height.0,
+            outcome,
+            quorum_reached,
+            quorum: Some(quorum),
+            action,
+            validator_config,
+            questions,
+        };
+        schema
+            .finalizations_mut()
+            .put(self.proposals_hash(), finalization);
+    }
+}
+
+impl Transaction for FinalizeBallot {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let proposals = self.precheck(view.as_ref())?;
+        self.save(view, proposals);
+        Ok(())
+    }
+}
+
+impl SponsorBallot {
+    fn precheck(&self, snapshot: &Snapshot) -> Result<Sponsorship, ServiceError> {
+        use self::ServiceError::*;
+
+        let schema = Schema::new(snapshot);
+        let ballot = schema
+            .ballot(self.proposals_hash())
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+        let proposals = ProposalList::try_deserialize(ballot.proposals().as_bytes())
+            .map_err(ServiceError::invalid_proposals)?;
+
+        let in_electorate = schema
+            .electorate(self.proposals_hash())
+            .map_or(false, |electorate| electorate.position(self.from()).is_some());
+        if !in_electorate {
+            Err(UnknownSender {
+                sender: *self.from(),
+            })?
+        }
+
+        let sponsorship = match schema.sponsorships().get(self.proposals_hash()) {
+            Some(sponsorship) if !sponsorship.is_open() => sponsorship,
+            _ => Err(NotSeekingSponsors {
+                proposals_hash: *self.proposals_hash(),
+            })?,
+        };
+
+        let latest_height = CoreSchema::new(snapshot).height();
+        if latest_height > Height(proposals.deadline()) {
+            Err(BallotAlreadyClosed {
+                deadline: proposals.deadline(),
+                height: latest_height.0,
+            })?
+        }
+
+        if ballot.from() == self.from() || sponsorship.sponsors.contains(self.from()) {
+            Err(AlreadySponsored {
+                sponsor: *self.from(),
+            })?
+        }
+
+        Ok(sponsorship)
+    }
+
+    fn save(&self, view: &mut Fork, mut sponsorship: Sponsorship) {
+        let height = CoreSchema::new(view.as_ref()).height().next();
+        sponsorship.sponsors.push(*self.from());
+        if sponsorship.sponsors.len() as u64 >= u64::from(sponsorship.min_sponsors) {
+            sponsorship.opened_at = Some(height.0);
+        }
+        Schema::new(view)
+            .sponsorships_mut()
+            .put(self.proposals_hash(), sponsorship);
+    }
+}
+
+impl Transaction for SponsorBallot {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let sponsorship = self.precheck(view.as_ref())?;
+        self.save(view, sponsorship);
+        Ok(())
+    }
+}
+
+impl AmendBallot {
+    fn precheck(&self, snapshot: &Snapshot) -> Result<(Amendment, ProposalList), ServiceError> {
+        use self::ServiceError::*;
+
+        let schema = Schema::new(snapshot);
+        let ballot = schema
+            .ballot(self.proposals_hash())
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+        if ballot.from() != self.from() {
+            Err(NotBallotAuthor {
+                sender: *self.from(),
+            })?
+        }
+
+        let proposals = schema
+            .proposals(self.proposals_hash())
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+        let latest_height = CoreSchema::new(snapshot).height();
+        if !proposals.in_discussion(latest_height) {
+            Err(DiscussionClosed {
+                proposals_hash: *self.proposals_hash(),
+                height: latest_height.0,
+            })?
+        }
+
+        check_revisions(snapshot, self.proposals_hash())?;
+        if self.amendment().len() > MAX_AMENDMENT_LEN {
+            Err(AmendmentTooLong {
+                len: self.amendment().len() as u64,
+                max: MAX_AMENDMENT_LEN as u64,
+            })?
+        }
+        let amendment = Amendment::try_deserialize(self.amendment().as_bytes())
+            .map_err(|e| InvalidAmendment {
+                message: e.to_string(),
+            })?;
+        if let Amendment::Edit { .. } | Amendment::Withdraw { .. } = amendment {
+            let proposal_id = amendment.proposal_id();
+            if let Some(nominator) = schema.proposal_added_by(self.proposals_hash(), proposal_id) {
+                if &nominator != self.from() {
+                    Err(NominatedProposal {
+                        proposal_id,
+                        nominator,
+                    })?
+                }
+            }
+        }
+        let amended = proposals
+            .amended(&amendment)
+            .ok_or_else(|| AmendedProposalNoneExists {
+                proposal_id: amendment.proposal_id(),
+            })?;
+
+        check_limits(&amended, &schema.limits())?;
+        check_actions(&amended)?;
+        if let Some(proposal_id) = amended.duplicate_id() {
+            Err(PostDuplicateProposalId { proposal_id })?
+        }
+
+        Ok((amendment, amended))
+    }
+
+    fn save(&self, view: &mut Fork, amendment: Amendment, amended: ProposalList) {
+        store_revision(view, self.proposals_hash(), self.from(), amendment, amended);
+    }
+}
+
+/// Checks that the ballot may get one more revision within `BallotLimits::max_revisions`.
+fn check_revisions(snapshot: &Snapshot, proposals_hash: &Hash) -> Result<(), ServiceError> {
+    let schema = Schema::new(snapshot);
+    let revisions = schema.revisions(proposals_hash).len();
+    let max = schema.limits().max_revisions;
+    if revisions >= u64::from(max) {
+        Err(ServiceError::TooManyRevisions {
+            proposals_hash: *proposals_hash,
+            revisions,
+            max,
+        })?
+    }
+    Ok(())
+}
+
+/// Appends the revised proposals of a ballot to its history.
+fn store_revision(
+    view: &mut Fork,
+    proposals_hash: &Hash,
+    from: &PublicKey,
+    amendment: Amendment,
+    revised: ProposalList,
+) {
+    let height = CoreSchema::new(view.as_ref()).height().next();
+    let mut schema = Schema::new(view);
+
+    // Votes wait for the discussion and nominations to end, the tally starts over empty.
+    schema
+        .tallies_mut()
+        .put(proposals_hash, Tally::new(&revised));
+
+    index_attachments(&mut schema, &revised);
+
+    let root = {
+        let mut revisions = schema.revisions_mut(proposals_hash);
+        revisions.push(Revision {
+            height: height.0,
+            from: *from,
+            amendment,
+            hash: revised.hash(),
+            proposals: revised,
+        });
+        revisions.merkle_root()
+    };
+    schema.revision_roots_mut().put(proposals_hash, root);
+}
+
+/// Adds the attachments of `proposals` missing from `ballot.attachments`.
+fn index_attachments(schema: &mut Schema<&mut Fork>, proposals: &ProposalList) {
+    let attachments = proposals
+        .proposals()
+        .iter()
+        .chain(proposals.questions().iter().flat_map(|q| q.proposals()))
+        .flat_map(|proposal| proposal.attachments());
+    let mut index = schema.attachments_mut();
+    for attachment in attachments {
+        if !index.contains(&attachment.hash) {
+            index.put(&attachment.hash, attachment.clone());
+        }
+    }
+}
+
+impl Transaction for AmendBallot {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let (amendment, amended) = self.precheck(view.as_ref())?;
+        self.save(view, amendment, amended);
+        Ok(())
+    }
+}
+
+impl Nominate {
+    fn precheck(&self, snapshot: &Snapshot) -> Result<(Amendment, ProposalList), ServiceError> {
+        use self::ServiceError::*;
+
+        let schema = Schema::new(snapshot);
+        let proposals = schema
+            .proposals(self.proposals_hash())
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+
+        let in_electorate = schema
+            .electorate(self.proposals_hash())
+            .map_or(false, |electorate| electorate.position(self.from()).is_some());
+        if !in_electorate {
+            Err(UnknownSender {
+                sender: *self.from(),
+            })?
+        }
+
+        let latest_height = CoreSchema::new(snapshot).height();
+        if !proposals.in_nominations(latest_height) {
+            Err(NominationsClosed {
+                proposals_hash: *self.proposals_hash(),
+                height: latest_height.0,
+            })?
+        }
+
+        check_revisions(snapshot, self.proposals_hash())?;
+        // Authors may amend their ballots instead, the revision cap limits them.
+        let is_author = schema
+            .ballot(self.proposals_hash())
+            .map_or(false, |ballot| ballot.from() == self.from());
+        if !is_author {
+            let nominations = schema
+                .revisions(self.proposals_hash())
+                .iter()
+                .filter(|revision| &revision.from == self.from())
+                .count() as u64;
+            let max = schema.limits().max_nominations_per_voter;
+            if nominations >= u64::from(max) {
+                Err(TooManyNominations { nominations, max })?
+            }
+        }
+        let amendment = Amendment::Add {
+            proposal: Proposal::new(proposals.next_id(), self.subject(), self.description()),
+        };
+        let nominated = proposals
+            .amended(&amendment)
+            .ok_or_else(|| AmendedProposalNoneExists {
+                proposal_id: amendment.proposal_id(),
+            })?;
+
+        check_limits(&nominated, &schema.limits())?;
+        if let Some(proposal_id) = nominated.duplicate_id() {
+            Err(PostDuplicateProposalId { proposal_id })?
+        }
+
+        Ok((amendment, nominated))
+    }
+}
+
+impl Transaction for Nominate {
+    fn verify(&self) -> bool {
+        self.verify_signature(self.from())
+    }
+
+    fn execute(&self, view: &mut Fork) -> ExecutionResult {
+        let (amendment, nominated) = self.precheck(view.as_ref())?;
+        store_revision(
+            view,
+            self.proposals_hash(),
+            self.from(),
+            amendment,
+            nominated,
+        );
+        Ok(())
+    }
+}
+
+impl VoteQuestions {
+    fn precheck(&self, snapshot: &Snapshot) -> Result<(BallotData, Vec<Answer>), ServiceError> {
+        use self::ServiceError::*;
+
+        let schema = Schema::new(snapshot);
+        let ballot_data = schema
+            .ballot_data_by_proposals_hash()
+            .get(self.proposals_hash())
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+
+        let in_electorate = schema
+            .electorate(self.proposals_hash())
+            .map_or(false, |electorate| electorate.position(self.from()).is_some());
+        if !in_electorate {
+            Err(UnknownSender {
+                sender: *self.from(),
+            })?
+        }
+        if schema
+            .answers()
+            .contains(&answer_sheet_key(self.proposals_hash(), self.from()))
+        {
+            Err(AlreadyVoted {
+                voter: *self.from(),
+            })?
+        }
+
+        let proposals = schema
+            .proposals(self.proposals_hash())
+            .ok_or_else(|| BallotNoneExists {
+                proposals_hash: *self.proposals_hash(),
+            })?;
+        if !proposals.has_questions() {
+            Err(InvalidAnswers {
+                message: "ballot has no questions, vote on its proposals".to_string(),
+            })?
+        }
+
+        let latest_height = CoreSchema::new(snapshot).height();
+        check_accepts_votes(&schema, self.proposals_hash(), &proposals, latest_height)?;
+
+        let answers = Answer::try_deserialize_list(self.answers().as_bytes())
+            .map_err(|e| InvalidAnswers {
+                message: e.to_string(),
+            })?;
+        check_answers(&proposals, &answers).map_err(|message| InvalidAnswers { message })?;
+
+        Ok((ballot_data, answers))
+    }
+
+    fn save(&self, view: &mut Fork, ballot_data: BallotData, answers: Vec<Answer>) {
+        let height = CoreSchema::new(view.as_ref()).height().next();
+        let mut schema = Schema::new(view);
+
+        // The vote list isn't touched, only the participation count.
+        let ballot_data = BallotData::new(
+            ballot_data.tx_ballot(),
+            ballot_data.votes_history_hash(),
+            ballot_data.electorate_size(),
+            ballot_data.votes_cast() + 1,
+        );
         schema
             .ballot_data_by_proposals_hash_mut()
             .put(self.proposals_hash(), ballot_data);
+
+        let proposals = schema.proposals(self.proposals_hash()).unwrap();
+        for answer in &answers {
+            let key = question_tally_key(self.proposals_hash(), answer.question_id);
+            let mut tally = match schema.tallies().get(&key) {
+                Some(tally) => tally,
+                None => Tally::for_question(proposals.question(answer.question_id).unwrap()),
+            };
+            for proposal_id in &answer.proposal_ids {
+                tally.add_vote(*proposal_id);
+            }
+            schema.tallies_mut().put(&key, tally);
+        }
+
+        schema.answers_mut().put(
+            &answer_sheet_key(self.proposals_hash(), self.from()),
+            AnswerSheet {
+                from: *self.from(),
+                height: height.0,
+                answers,
+            },
+        );
+        schema
+            .answers_by_voter_mut(self.from())
+            .push(*self.proposals_hash());
     }
 }
 
-impl Transaction for Vote {
+/// Describes why `answers` isn't exactly one valid answer per question, if it isn't.
+fn check_answers(proposals: &ProposalList, answers: &[Answer]) -> Result<(), String> {
+    for (i, answer) in answers.iter().enumerate() {
+        if answers[..i]
+            .iter()
+            .any(|other| other.question_id == answer.question_id)
+        {
+            Err(format!("question {} is answered twice", answer.question_id))?
+        }
+        proposals
+            .question(answer.question_id)
+            .ok_or_else(|| format!("ballot has no question {}", answer.question_id))?
+            .check_answer(&answer.proposal_ids)?;
+    }
+    for question in proposals.questions() {
+        if !answers
+            .iter()
+            .any(|answer| answer.question_id == question.id())
+        {
+            Err(format!("question {} isn't answered", question.id()))?
+        }
+    }
+    Ok(())
+}
+
+impl Transaction for VoteQuestions {
     fn verify(&self) -> bool {
         self.verify_signature(self.from())
     }
 
     fn execute(&self, view: &mut Fork) -> ExecutionResult {
-        let (ballot_data, validator_id) = self.precheck(view.as_ref())?;
-        self.save(view, ballot_data, validator_id as u64);
+        let (ballot_data, answers) = self.precheck(view.as_ref())?;
+        self.save(view, ballot_data, answers);
         Ok(())
     }
 }
@@ -0,0 +1,103 @@
+use exonum::blockchain::{ServiceContext, StoredConfiguration};
+use exonum::crypto::{Hash, PublicKey};
+use exonum::helpers::Height;
+use exonum::storage::Snapshot;
+use exonum_configuration::{Propose, Schema as ConfigurationSchema, Vote as ConfigVote};
+use serde_json;
+
+use schema::{Outcome, Schema};
+
+/// Maps finalized `ChangeValidators` ballots onto the configuration service.
+///
+/// After every block, each validator that voted for the winning proposal of such a
+/// ballot sends `Propose` with `Finalization::validator_config` while the configuration
+/// service has no propose for it, and a configuration `Vote` while its vote on the
+/// propose is missing. This goes on until the configuration is due, so the change
+/// passes if enough validators backed it on the ballot, even if some of them are
+/// offline for a while. Repeated transactions are identical and the pool keeps one.
+///
+/// Configurations due by the next block are expired: they're skipped here, so nothing
+/// is sent for them that could still apply, and the next `FinalizeBallot` drops them
+/// from `ballot.ballots_by_validator_config`.
+pub(crate) fn handle_commit(context: &ServiceContext) {
+    if context.validator_id().is_none() {
+        return;
+    }
+
+    let snapshot = context.snapshot();
+    let schema = Schema::new(snapshot);
+    let config_schema = ConfigurationSchema::new(snapshot);
+    // The configuration service only accepts proposes activating after the next block.
+    let next_height = context.height().next();
+
+    for (config_hash, proposals_hash) in schema.ballots_by_validator_config().iter() {
+        let config = match pending_config(snapshot, &proposals_hash) {
+            Some(config) => config,
+            None => continue,
+        };
+        if is_expired(&config, next_height)
+            || !supporters(snapshot, &proposals_hash).contains(context.public_key())
+        {
+            continue;
+        }
+
+        if config_schema.get_propose(&config_hash).is_none() {
+            send_propose(context, &config);
+        } else {
+            let voted = config_schema
+                .get_votes(&config_hash)
+                .into_iter()
+                .filter_map(|vote| vote)
+                .any(|vote| vote.from() == context.public_key());
+            if !voted {
+                send_vote(context, &config_hash);
+            }
+        }
+    }
+}
+
+fn is_expired(config: &StoredConfiguration, next_height: Height) -> bool {
+    config.actual_from <= next_height
+}
+
+fn pending_config(snapshot: &Snapshot, proposals_hash: &Hash) -> Option<StoredConfiguration> {
+    Schema::new(snapshot)
+        .finalizations()
+        .get(proposals_hash)?
+        .validator_config
+}
+
+fn send_propose(context: &ServiceContext, config: &StoredConfiguration) {
+    let propose = Propose::new(
+        context.public_key(),
+        &serde_json::to_string(config).unwrap(),
+        context.secret_key(),
+    );
+    // Sending only fails while the node shuts down.
+    let _ = context.transaction_sender().send(Box::new(propose));
+}
+
+fn send_vote(context: &ServiceContext, config_hash: &Hash) {
+    let vote = ConfigVote::new(context.public_key(), config_hash, context.secret_key());
+    let _ = context.transaction_sender().send(Box::new(vote));
+}
+
+/// Keys that voted for the winning proposal of a finalized ballot, in electorate order.
+fn supporters(snapshot: &Snapshot, proposals_hash: &Hash) -> Vec<PublicKey> {
+    let schema = Schema::new(snapshot);
+    let winner = match schema.finalizations().get(proposals_hash) {
+        Some(finalization) => match finalization.outcome {
+            Outcome::Winner { proposal_id } => proposal_id,
+            _ => return Vec::new(),
+        },
+        None => return Vec::new(),
+    };
+
+    schema
+        .votes(proposals_hash)
+        .into_iter()
+        .filter_map(|vote| vote)
+        .filter(|vote| vote.proposal_id() == winner)
+        .map(|vote| *vote.from())
+        .collect()
+}